@@ -16,6 +16,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         departure: 0,
         duration: 90000,
         weekday: "monday",
+        service_date: None,
+        cache_dir: None,
+        walk_speed: 1.39,
+        max_transfer_distance: 1000.0,
     };
 
     let instant = std::time::Instant::now();
@@ -28,7 +32,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let instant = std::time::Instant::now();
     //let path = single_source_shortest_path_weight(&transit_graph, &source, 43200);
 
-    let path = detailed_itinerary(&transit_graph, &source, &target, 43200);
+    let path = detailed_itinerary(&transit_graph, &source, &target, 43200, false, None);
 
     println!("Path: {:#?}", path);
     println!("Dijkstra time: {:?}", instant.elapsed());
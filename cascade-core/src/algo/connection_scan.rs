@@ -0,0 +1,286 @@
+//! Connection Scan Algorithm (CSA) — a precompute-once alternative to
+//! repeatedly re-running [`time_dependent_dijkstra`](crate::algo::dijkstra::time_dependent_dijkstra)
+//! for every query against the same [`TransitGraph`].
+//!
+//! [`ConnectionIndex::build`] flattens every [`TransitEdge`](crate::graph::TransitEdge)'s
+//! trips into a single array of elementary connections, sorted ascending by departure
+//! time, once per graph. [`ConnectionIndex::earliest_arrival`] then answers a single
+//! query by scanning that array in one O(connections) pass with no heap, instead of
+//! re-expanding edges from scratch — a good trade for dense feeds and repeated
+//! many-to-one queries against the same graph snapshot.
+
+use ahash::{HashMap, HashMapExt};
+use petgraph::graph::{EdgeIndex, NodeIndex};
+use petgraph::visit::EdgeRef;
+
+use crate::algo::itinerary::segment::{segment_geometry, Itinerary, Segment};
+use crate::graph::{GraphEdge, QueryOptions, TransitGraph};
+use crate::interner::{RouteId, StopId, TripId};
+use crate::prelude::SnappedPoint;
+use crate::realtime::RealtimeOverlay;
+
+/// A single elementary connection: one trip's hop between the two stops an
+/// edge directly connects. `edge`/`trip_index` locate the originating
+/// [`Trip`](crate::graph::Trip) so [`ConnectionIndex::reconstruct`] can build
+/// the same [`Segment::Transit`] output a Dijkstra-based search would.
+#[derive(Debug, Clone, Copy)]
+struct Connection {
+    from_node: NodeIndex,
+    to_node: NodeIndex,
+    dep_time: u32,
+    arr_time: u32,
+    route_id: RouteId,
+    trip_id: TripId,
+    source_stop: StopId,
+    target_stop: StopId,
+    edge: EdgeIndex,
+    trip_index: usize,
+}
+
+/// Precomputed, sorted-by-departure array of every elementary connection in
+/// a [`TransitGraph`], built once and reused across queries.
+///
+/// Only trips carried on [`GraphEdge::Transit`] edges become connections —
+/// walk and transfer edges aren't time-dependent, so they don't fit the flat
+/// connection-array model and are outside this algorithm's scope (as is
+/// reasoning about overnight trips that wrap past midnight more than once).
+pub struct ConnectionIndex {
+    connections: Vec<Connection>,
+}
+
+impl ConnectionIndex {
+    /// Scans every transit edge in `graph` and flattens its trips into the
+    /// sorted connection array.
+    #[must_use]
+    pub fn build(graph: &TransitGraph) -> Self {
+        let mut connections: Vec<Connection> = graph
+            .edge_references()
+            .filter_map(|edge| match edge.weight() {
+                GraphEdge::Transit(transit_edge) => Some((edge, transit_edge)),
+                GraphEdge::Transfer(_) | GraphEdge::Walk(_) => None,
+            })
+            .flat_map(|(edge, transit_edge)| {
+                transit_edge
+                    .edge_trips
+                    .iter()
+                    .enumerate()
+                    .map(move |(trip_index, trip)| Connection {
+                        from_node: edge.source(),
+                        to_node: edge.target(),
+                        dep_time: trip.departure_time,
+                        arr_time: trip.arrival_time,
+                        route_id: trip.route_id,
+                        trip_id: trip.trip_id,
+                        source_stop: transit_edge.source_stop,
+                        target_stop: transit_edge.target_stop,
+                        edge: edge.id(),
+                        trip_index,
+                    })
+            })
+            .collect();
+
+        connections.sort_by_key(|connection| connection.dep_time);
+        Self { connections }
+    }
+
+    /// Scans the precomputed connection array once from `start`: `earliest_arrival`
+    /// starts at `start_time` for `start` and `u32::MAX` everywhere else, and each
+    /// connection in departure order relaxes `earliest_arrival[to]` whenever it
+    /// departs at or after `earliest_arrival[from]` and arrives earlier than what's
+    /// currently recorded for `to`. When `target` is given, the scan stops early
+    /// once every connection still to come departs after `target` has already been
+    /// reached; with no `target` the full array is scanned so every reachable node
+    /// gets a distance, which is what a one-to-many query needs.
+    ///
+    /// Shared by [`earliest_arrival`](Self::earliest_arrival) and
+    /// [`earliest_arrivals_from`](Self::earliest_arrivals_from) — `self` is only
+    /// ever read here, so the same `ConnectionIndex` can back many concurrent
+    /// scans with no locking.
+    fn scan(
+        &self,
+        graph: &TransitGraph,
+        node_count: usize,
+        start: NodeIndex,
+        target: Option<NodeIndex>,
+        start_time: u32,
+        options: &QueryOptions,
+        overlay: Option<&RealtimeOverlay>,
+    ) -> (Vec<u32>, HashMap<NodeIndex, Connection>) {
+        let mut earliest_arrival = vec![u32::MAX; node_count];
+        earliest_arrival[start.index()] = start_time;
+        let mut predecessor: HashMap<NodeIndex, Connection> = HashMap::new();
+
+        for connection in &self.connections {
+            if let Some(target) = target {
+                if connection.dep_time > earliest_arrival[target.index()] {
+                    break;
+                }
+            }
+
+            if let Some(overlay) = overlay {
+                if overlay.is_canceled(connection.trip_id) {
+                    continue;
+                }
+            }
+
+            if options.wheelchair {
+                let accessible = graph.edge_weight(connection.edge).is_some_and(|edge| {
+                    matches!(edge, GraphEdge::Transit(transit_edge)
+                        if transit_edge.boarding_wheelchair_accessible
+                            && transit_edge.edge_trips[connection.trip_index].wheelchair_accessible)
+                });
+                if !accessible {
+                    continue;
+                }
+            }
+
+            let departure = overlay.map_or(connection.dep_time, |overlay| {
+                overlay.adjust_departure(connection.trip_id, connection.source_stop, connection.dep_time)
+            });
+
+            if departure < earliest_arrival[connection.from_node.index()] {
+                continue;
+            }
+
+            let arrival = overlay.map_or(connection.arr_time, |overlay| {
+                overlay.adjust_arrival(connection.trip_id, connection.target_stop, connection.arr_time)
+            });
+
+            if arrival < earliest_arrival[connection.to_node.index()] {
+                earliest_arrival[connection.to_node.index()] = arrival;
+                predecessor.insert(connection.to_node, *connection);
+            }
+        }
+
+        (earliest_arrival, predecessor)
+    }
+
+    /// Answers a single earliest-arrival query from `start` to `target`.
+    ///
+    /// Returns `None` if `target` is unreachable from `start` at `start_time`.
+    #[must_use]
+    pub fn earliest_arrival<'a>(
+        &self,
+        graph: &'a TransitGraph,
+        start: NodeIndex,
+        target: NodeIndex,
+        start_time: u32,
+        options: &QueryOptions,
+        overlay: Option<&RealtimeOverlay>,
+    ) -> Option<Itinerary<'a>> {
+        let node_count = graph.into_inner_graph().node_count();
+        let (earliest_arrival, predecessor) =
+            self.scan(graph, node_count, start, Some(target), start_time, options, overlay);
+
+        if earliest_arrival[target.index()] == u32::MAX {
+            return None;
+        }
+
+        Some(self.reconstruct(graph, start, target, &earliest_arrival, &predecessor))
+    }
+
+    /// Answers a one-to-many earliest-arrival query from `start` to every node
+    /// reachable in the connection array, scanning the full array once (there's
+    /// no single target to stop early at). This is the per-source worker behind
+    /// [`crate::algo::matrix::connection_scan_matrix`]: since `self` is read-only
+    /// here, many of these scans can run concurrently over one shared
+    /// `ConnectionIndex`.
+    ///
+    /// Returns a map of reachable nodes to their travel time past `start_time`,
+    /// matching [`crate::algo::single_source_shortest_path_weight`]'s convention
+    /// of leaving an unreachable node absent rather than recording it as infinite.
+    #[must_use]
+    pub fn earliest_arrivals_from(
+        &self,
+        graph: &TransitGraph,
+        start: NodeIndex,
+        start_time: u32,
+        options: &QueryOptions,
+        overlay: Option<&RealtimeOverlay>,
+    ) -> hashbrown::HashMap<NodeIndex, f64> {
+        let node_count = graph.into_inner_graph().node_count();
+        let (earliest_arrival, _) = self.scan(graph, node_count, start, None, start_time, options, overlay);
+
+        earliest_arrival
+            .into_iter()
+            .enumerate()
+            .filter(|&(_, arrival)| arrival != u32::MAX)
+            .map(|(index, arrival)| (NodeIndex::new(index), f64::from(arrival - start_time)))
+            .collect()
+    }
+
+    /// Walks `predecessor` connections backward from `target` to `start`, then
+    /// replays them forward into an [`Itinerary`], matching the same
+    /// [`Segment::Transit`] shape a Dijkstra-based search produces.
+    fn reconstruct<'a>(
+        &self,
+        graph: &'a TransitGraph,
+        start: NodeIndex,
+        target: NodeIndex,
+        earliest_arrival: &[u32],
+        predecessor: &HashMap<NodeIndex, Connection>,
+    ) -> Itinerary<'a> {
+        let mut chain = Vec::new();
+        let mut current = target;
+        while current != start {
+            let Some(connection) = predecessor.get(&current) else {
+                break;
+            };
+            chain.push(*connection);
+            current = connection.from_node;
+        }
+        chain.reverse();
+
+        let mut itinerary = Itinerary::new();
+        for connection in chain {
+            let Some(GraphEdge::Transit(transit_edge)) = graph.edge_weight(connection.edge) else {
+                continue;
+            };
+            let trip = &transit_edge.edge_trips[connection.trip_index];
+            let current_time = earliest_arrival[connection.from_node.index()];
+            let arrival = earliest_arrival[connection.to_node.index()];
+
+            itinerary.push(Segment::Transit {
+                trip,
+                weight: f64::from(arrival - current_time),
+                geometry: segment_geometry(
+                    graph,
+                    transit_edge.geometry.as_ref(),
+                    connection.from_node,
+                    connection.to_node,
+                ),
+            });
+        }
+
+        itinerary
+    }
+}
+
+/// Computes an itinerary between two locations using a precomputed
+/// [`ConnectionIndex`] instead of a fresh Dijkstra search.
+///
+/// Useful when many queries are run against the same `graph` snapshot: the
+/// caller builds one [`ConnectionIndex`] with [`ConnectionIndex::build`] and
+/// reuses it across every `connection_scan_itinerary` call.
+///
+/// Returns an empty `Itinerary` if `target` is unreachable from `start` at
+/// `start_time`.
+#[must_use]
+pub fn connection_scan_itinerary<'a, 'b>(
+    graph: &'a TransitGraph,
+    index: &ConnectionIndex,
+    start: &'b SnappedPoint,
+    target: &'b SnappedPoint,
+    start_time: u32,
+    wheelchair: bool,
+    overlay: Option<&RealtimeOverlay>,
+) -> Itinerary<'a> {
+    let options = QueryOptions {
+        wheelchair,
+        ..QueryOptions::default()
+    };
+
+    index
+        .earliest_arrival(graph, *start.index(), *target.index(), start_time, &options, overlay)
+        .unwrap_or_else(Itinerary::new)
+}
@@ -0,0 +1,110 @@
+//! A d-ary max-heap, swapped in for [`std::collections::BinaryHeap`] on the
+//! hot time-dependent search loop.
+//!
+//! A d-ary heap has `log_d(n)` height instead of `log_2(n)`: each level
+//! holds `ARITY` times as many children, so sift-down visits fewer, wider
+//! levels instead of many narrow ones, which empirically reduces cache
+//! misses on the decrease-key-heavy frontier churn of Dijkstra-style search.
+//! [`DaryHeap`] has the exact push/pop-max shape `BinaryHeap` is used for in
+//! [`crate::algo::dijkstra`]: no decrease-key support, callers push a fresh
+//! entry whenever a node's score improves and skip stale entries they pop
+//! later (the existing lazy-deletion scheme).
+
+/// Branching factor of [`DaryHeap`]. `4` halves the tree height versus a
+/// binary heap while keeping each level's linear scan for the
+/// largest child cheap; tune and re-benchmark against
+/// [`std::collections::BinaryHeap`] here if access patterns change.
+pub(crate) const HEAP_ARITY: usize = 4;
+
+/// Max-heap ordered by `T`'s [`Ord`] impl, with a configurable branching
+/// factor ([`HEAP_ARITY`]) instead of the fixed arity of 2 used by
+/// [`std::collections::BinaryHeap`].
+#[derive(Debug, Clone, Default)]
+pub(crate) struct DaryHeap<T> {
+    data: Vec<T>,
+}
+
+impl<T: Ord> DaryHeap<T> {
+    pub(crate) fn new() -> Self {
+        Self { data: Vec::new() }
+    }
+
+    pub(crate) fn push(&mut self, item: T) {
+        self.data.push(item);
+        self.sift_up(self.data.len() - 1);
+    }
+
+    pub(crate) fn pop(&mut self) -> Option<T> {
+        if self.data.is_empty() {
+            return None;
+        }
+        let last = self.data.len() - 1;
+        self.data.swap(0, last);
+        let item = self.data.pop();
+        if !self.data.is_empty() {
+            self.sift_down(0);
+        }
+        item
+    }
+
+    fn sift_up(&mut self, mut index: usize) {
+        while index > 0 {
+            let parent = (index - 1) / HEAP_ARITY;
+            if self.data[index] > self.data[parent] {
+                self.data.swap(index, parent);
+                index = parent;
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn sift_down(&mut self, mut index: usize) {
+        loop {
+            let first_child = index * HEAP_ARITY + 1;
+            if first_child >= self.data.len() {
+                break;
+            }
+            let last_child = (first_child + HEAP_ARITY).min(self.data.len());
+
+            let mut largest = index;
+            for child in first_child..last_child {
+                if self.data[child] > self.data[largest] {
+                    largest = child;
+                }
+            }
+
+            if largest == index {
+                break;
+            }
+            self.data.swap(index, largest);
+            index = largest;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pop_returns_descending_order() {
+        let mut heap = DaryHeap::new();
+        for value in [5, 1, 4, 2, 8, 9, 3] {
+            heap.push(value);
+        }
+
+        let mut popped = Vec::new();
+        while let Some(value) = heap.pop() {
+            popped.push(value);
+        }
+
+        assert_eq!(popped, vec![9, 8, 5, 4, 3, 2, 1]);
+    }
+
+    #[test]
+    fn test_pop_empty_returns_none() {
+        let mut heap: DaryHeap<i32> = DaryHeap::new();
+        assert_eq!(heap.pop(), None);
+    }
+}
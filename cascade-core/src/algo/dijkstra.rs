@@ -5,43 +5,162 @@
 //! Implementation is based on classic Dijkstra's algorithm implementation in the [`petgraph`] crate
 //! and Time-dependent Dijkstra's algorithm implementation in the `Nxtransit` python library.
 
-use std::collections::BinaryHeap;
-
+use geo::prelude::*;
 use hashbrown::hash_map::Entry::{Occupied, Vacant};
 use hashbrown::{HashMap, HashSet};
-use petgraph::graph::NodeIndex;
+use petgraph::graph::{EdgeIndex, NodeIndex};
 use petgraph::visit::EdgeRef;
 
+use crate::algo::dary_heap::DaryHeap;
+use crate::algo::itinerary::segment::segment_geometry;
+use crate::algo::itinerary::Itinerary;
 use crate::algo::MinScored;
-use crate::graph::TransitGraph;
+use crate::graph::{GraphNode, QueryOptions, TransitGraph};
+use crate::realtime::RealtimeOverlay;
+
+/// Result of a [`time_dependent_dijkstra`] search: the settled shortest-path
+/// weights, plus enough predecessor information to walk any reachable node's
+/// path back to the source and turn it into a full [`Itinerary`].
+pub(crate) struct PathSearch {
+    pub(crate) scores: HashMap<NodeIndex, f64>,
+    /// For each settled node (other than the source), the previous node, the
+    /// edge taken to reach it, and the arrival time at the previous node —
+    /// i.e. the `current_time` the boarded trip/walk was evaluated at.
+    predecessors: HashMap<NodeIndex, (NodeIndex, EdgeIndex, u32)>,
+}
+
+impl PathSearch {
+    /// Walks predecessors from `target` back to the search's source and
+    /// replays each edge with [`GraphEdge::calculate_itinerary`] to produce
+    /// an ordered [`Itinerary`]. Returns `None` if `target` was never
+    /// reached.
+    #[must_use]
+    pub(crate) fn reconstruct_itinerary<'a>(
+        &self,
+        graph: &'a TransitGraph,
+        target: NodeIndex,
+        options: &QueryOptions,
+        overlay: Option<&RealtimeOverlay>,
+    ) -> Option<Itinerary<'a>> {
+        if !self.scores.contains_key(&target) {
+            return None;
+        }
+
+        let mut itinerary = Itinerary::new();
+        let mut current = target;
+
+        while let Some(&(prev, edge_index, prev_arrival_time)) = self.predecessors.get(&current) {
+            let edge = graph.into_inner_graph().edge_weight(edge_index)?;
+            let geometry = segment_geometry(graph, edge.geometry(), prev, current);
+            itinerary.push(edge.calculate_itinerary(prev_arrival_time, geometry, options, overlay));
+            current = prev;
+        }
+
+        itinerary.segments.reverse();
+        Some(itinerary)
+    }
+
+    /// Walks predecessors from `target` back to the search's source,
+    /// returning the ordered edge indices traversed. Unlike
+    /// [`Self::reconstruct_itinerary`], this doesn't replay
+    /// [`GraphEdge::calculate_itinerary`](crate::graph::GraphEdge::calculate_itinerary) —
+    /// it's for callers that only need the raw edges a path crosses, such as
+    /// [`crate::algo::steiner::steiner_network`] unioning edges shared by
+    /// several terminal-pair paths. Returns `None` if `target` was never
+    /// reached.
+    #[must_use]
+    pub(crate) fn path_edges(&self, target: NodeIndex) -> Option<Vec<EdgeIndex>> {
+        if !self.scores.contains_key(&target) {
+            return None;
+        }
+
+        let mut edges = Vec::new();
+        let mut current = target;
+
+        while let Some(&(prev, edge_index, _)) = self.predecessors.get(&current) {
+            edges.push(edge_index);
+            current = prev;
+        }
+
+        edges.reverse();
+        Some(edges)
+    }
+}
 
 ///  Finds the shortest paths from source node in a time-dependent graph using Dijkstra's algorithm.
 /// # Arguments
 /// * `graph` - A reference to a `TransitGraph` object.
 /// * `start` - The source node index.
 /// * `start_time` - The starting time in seconds since midnight.
+/// * `overlay` - An optional [`RealtimeOverlay`] whose live delays and
+///   cancellations are consulted instead of the static schedule alone.
+/// * `options` - Rider constraints consulted by [`GraphEdge::calculate_delay`]
+///   so infeasible edges (e.g. a trip that isn't wheelchair accessible) are
+///   never settled on in the first place.
 /// # Returns
-/// A `HashMap` with the shortest path weight in seconds to each node from the source node.
+/// A [`PathSearch`] holding the shortest path weight in seconds to each node
+/// from the source node, and the predecessor chain needed to reconstruct a
+/// full itinerary to any of them via [`PathSearch::reconstruct_itinerary`].
 /// # Implementation notes
 /// This function uses a priority queue to explore the graph with
 /// almost classic Dijkstra's algorithm. The main difference is that the
 /// delay between two nodes is calculated based on the `current time`
 /// and the sorted schedules of the edge.
 #[must_use]
-#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
 pub(crate) fn time_dependent_dijkstra(
     graph: &TransitGraph,
     start: NodeIndex,
     target: Option<NodeIndex>,
     start_time: u32,
-) -> HashMap<NodeIndex, f64> {
+    overlay: Option<&RealtimeOverlay>,
+    options: &QueryOptions,
+) -> PathSearch {
+    time_dependent_dijkstra_multi_start(graph, &[(start, 0.0)], target, start_time, overlay, options)
+}
+
+/// Finds the shortest paths from a walk-access catchment of several starting
+/// nodes, like [`time_dependent_dijkstra`], but seeds the heap with one entry
+/// per `(node, walk_access_time)` pair in `starts` instead of a single
+/// zero-weight source.
+///
+/// This models a traveler who can reach several nearby stops on foot (e.g.
+/// every node within [`SnappedPoint::init_within`](crate::connectors::SnappedPoint::init_within)'s
+/// radius of a query point) rather than being forced through one artificial
+/// nearest-node access link: each start's own `walk_access_time` becomes its
+/// initial score, and `start_time + walk_access_time` becomes its clock for
+/// schedule lookups, exactly as if a zero-weight search had already walked
+/// that far before the timed portion began.
+/// # Implementation notes
+/// This function uses a priority queue to explore the graph with
+/// almost classic Dijkstra's algorithm. The main difference is that the
+/// delay between two nodes is calculated based on the `current time`
+/// and the sorted schedules of the edge.
+#[must_use]
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+pub(crate) fn time_dependent_dijkstra_multi_start(
+    graph: &TransitGraph,
+    starts: &[(NodeIndex, f64)],
+    target: Option<NodeIndex>,
+    start_time: u32,
+    overlay: Option<&RealtimeOverlay>,
+    options: &QueryOptions,
+) -> PathSearch {
     let mut visited = HashSet::new();
     let mut scores: HashMap<NodeIndex, f64> =
         HashMap::with_capacity(graph.into_inner_graph().node_count());
+    let mut predecessors: HashMap<NodeIndex, (NodeIndex, EdgeIndex, u32)> = HashMap::new();
 
-    let mut visit_next = BinaryHeap::new();
-    scores.insert(start, 0.0);
-    visit_next.push(MinScored(0.0, (start, start_time)));
+    let mut visit_next = DaryHeap::new();
+    for &(start, walk_access_time) in starts {
+        let improves = scores
+            .get(&start)
+            .map_or(true, |&existing| walk_access_time < existing);
+        if improves {
+            scores.insert(start, walk_access_time);
+        }
+        let clock = start_time + walk_access_time as u32;
+        visit_next.push(MinScored(walk_access_time, (start, clock)));
+    }
 
     while let Some(MinScored(node_score, (node, current_time))) = visit_next.pop() {
         if visited.contains(&node) {
@@ -60,7 +179,7 @@ pub(crate) fn time_dependent_dijkstra(
                 continue;
             }
 
-            let travel_time = edge.weight().calculate_delay(current_time);
+            let travel_time = edge.weight().calculate_delay(current_time, overlay, options);
             if travel_time.is_infinite() {
                 continue;
             }
@@ -73,15 +192,252 @@ pub(crate) fn time_dependent_dijkstra(
                     if next_score < *ent.get() {
                         ent.insert(next_score);
                         visit_next.push(MinScored(next_score, (next, next_time)));
+                        predecessors.insert(next, (node, edge.id(), current_time));
                     }
                 }
                 Vacant(ent) => {
                     ent.insert(next_score);
                     visit_next.push(MinScored(next_score, (next, next_time)));
+                    predecessors.insert(next, (node, edge.id(), current_time));
+                }
+            }
+        }
+        visited.insert(node);
+    }
+    PathSearch {
+        scores,
+        predecessors,
+    }
+}
+
+/// Finds the shortest path from `start` to `target` in a time-dependent graph
+/// using an A*-guided variant of [`time_dependent_dijkstra`].
+/// # Arguments
+/// * `graph` - A reference to a `TransitGraph` object.
+/// * `start` - The source node index.
+/// * `target` - The target node index.
+/// * `start_time` - The starting time in seconds since midnight.
+/// * `overlay` - An optional [`RealtimeOverlay`] whose live delays and
+///   cancellations are consulted instead of the static schedule alone.
+/// * `options` - Rider constraints consulted by `calculate_delay` so
+///   infeasible edges are never settled on in the first place.
+/// # Returns
+/// A [`PathSearch`] holding the shortest path weight in seconds to each node
+/// visited before `target` was popped, and the predecessor chain needed to
+/// reconstruct a full itinerary via [`PathSearch::reconstruct_itinerary`].
+/// Identical result for `target` to `time_dependent_dijkstra`, but explores
+/// far fewer nodes for long single-destination trips.
+/// # Implementation notes
+/// The heap is ordered by `g + h` instead of the true cost `g`, where
+/// `h(n) = haversine_distance(n, target) / graph.max_edge_speed()` is an
+/// admissible lower bound on the remaining travel time: no edge can reach
+/// `target` faster than the straight-line distance divided by the fastest
+/// possible mode in the graph. The true arrival time `current_time` is
+/// still threaded through the heap tuple unchanged, so `calculate_delay`
+/// keeps working exactly as it does for plain Dijkstra; only the priority
+/// used to order the heap is inflated by `h`, and `scores` always holds the
+/// real, un-inflated cost.
+#[must_use]
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+pub(crate) fn time_dependent_a_star(
+    graph: &TransitGraph,
+    start: NodeIndex,
+    target: NodeIndex,
+    start_time: u32,
+    overlay: Option<&RealtimeOverlay>,
+    options: &QueryOptions,
+) -> PathSearch {
+    let max_edge_speed = graph.max_edge_speed();
+    let heuristic = |node: NodeIndex| -> f64 {
+        let (Some(node_geometry), Some(target_geometry)) = (
+            graph.node_weight(node).map(GraphNode::geometry),
+            graph.node_weight(target).map(GraphNode::geometry),
+        ) else {
+            return 0.0;
+        };
+        node_geometry.haversine_distance(target_geometry) / max_edge_speed
+    };
+
+    let mut visited = HashSet::new();
+    let mut scores: HashMap<NodeIndex, f64> =
+        HashMap::with_capacity(graph.into_inner_graph().node_count());
+    let mut predecessors: HashMap<NodeIndex, (NodeIndex, EdgeIndex, u32)> = HashMap::new();
+
+    let mut visit_next = DaryHeap::new();
+    scores.insert(start, 0.0);
+    visit_next.push(MinScored(heuristic(start), (start, start_time)));
+
+    while let Some(MinScored(_, (node, current_time))) = visit_next.pop() {
+        if visited.contains(&node) {
+            continue;
+        }
+
+        let node_score = scores[&node];
+
+        if node == target {
+            break;
+        }
+
+        for edge in graph.edges(node) {
+            let next = edge.target();
+            if visited.contains(&next) {
+                continue;
+            }
+
+            let travel_time = edge.weight().calculate_delay(current_time, overlay, options);
+            if travel_time.is_infinite() {
+                continue;
+            }
+
+            let next_score = node_score + travel_time;
+            let next_time = current_time + travel_time as u32;
+
+            match scores.entry(next) {
+                Occupied(mut ent) => {
+                    if next_score < *ent.get() {
+                        ent.insert(next_score);
+                        visit_next.push(MinScored(next_score + heuristic(next), (next, next_time)));
+                        predecessors.insert(next, (node, edge.id(), current_time));
+                    }
+                }
+                Vacant(ent) => {
+                    ent.insert(next_score);
+                    visit_next.push(MinScored(next_score + heuristic(next), (next, next_time)));
+                    predecessors.insert(next, (node, edge.id(), current_time));
                 }
             }
         }
         visited.insert(node);
     }
-    scores
+    PathSearch {
+        scores,
+        predecessors,
+    }
+}
+
+/// Finds a path from `start` to `target` like [`time_dependent_a_star`], but
+/// caps the number of states carried between frontiers to `beam_width` when
+/// it's `Some`, trading optimality for a bounded memory/latency footprint on
+/// very large graphs. `beam_width: None` runs the exact, unbounded search.
+/// # Arguments
+/// * `graph` - A reference to a `TransitGraph` object.
+/// * `start` - The source node index.
+/// * `target` - The target node index.
+/// * `start_time` - The starting time in seconds since midnight.
+/// * `overlay` - An optional [`RealtimeOverlay`] whose live delays and
+///   cancellations are consulted instead of the static schedule alone.
+/// * `options` - Rider constraints consulted by `calculate_delay` so
+///   infeasible edges are never settled on in the first place.
+/// * `beam_width` - The maximum number of `(node, time)` states kept in the
+///   frontier after each level; `None` runs [`time_dependent_a_star`]
+///   instead.
+/// # Returns
+/// A [`PathSearch`] holding the shortest weight found to each node the beam
+/// reached, and the predecessor chain needed to reconstruct an itinerary via
+/// [`PathSearch::reconstruct_itinerary`]. When the beam prunes away the true
+/// optimal path, the result is heuristic: still a valid connected path, but
+/// not guaranteed shortest.
+/// # Implementation notes
+/// Unlike [`time_dependent_a_star`], which pops one state at a time off a
+/// single priority queue, this processes the frontier one whole level at a
+/// time: every state in the current frontier is expanded, every resulting
+/// `(node, time)` candidate is scored by `g + h` and pushed onto a
+/// [`DaryHeap`], and then up to `beam_width` of the best candidates are
+/// popped back off to form the next frontier — the rest are discarded. A
+/// node is never expanded twice, so the beam can't cycle.
+#[must_use]
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+pub(crate) fn time_dependent_beam_search(
+    graph: &TransitGraph,
+    start: NodeIndex,
+    target: NodeIndex,
+    start_time: u32,
+    overlay: Option<&RealtimeOverlay>,
+    options: &QueryOptions,
+    beam_width: Option<usize>,
+) -> PathSearch {
+    if beam_width.is_none() {
+        return time_dependent_a_star(graph, start, target, start_time, overlay, options);
+    }
+
+    let heuristic = |node: NodeIndex| -> f64 {
+        let (Some(node_geometry), Some(target_geometry)) = (
+            graph.node_weight(node).map(GraphNode::geometry),
+            graph.node_weight(target).map(GraphNode::geometry),
+        ) else {
+            return 0.0;
+        };
+        node_geometry.haversine_distance(target_geometry) / graph.max_edge_speed()
+    };
+
+    let mut visited = HashSet::new();
+    let mut scores: HashMap<NodeIndex, f64> =
+        HashMap::with_capacity(graph.into_inner_graph().node_count());
+    let mut predecessors: HashMap<NodeIndex, (NodeIndex, EdgeIndex, u32)> = HashMap::new();
+
+    scores.insert(start, 0.0);
+    let mut frontier = vec![(start, start_time)];
+
+    while !frontier.is_empty() && !frontier.iter().any(|&(node, _)| node == target) {
+        let mut next_candidates: HashMap<NodeIndex, (f64, u32)> = HashMap::new();
+
+        for (node, current_time) in frontier {
+            if !visited.insert(node) {
+                continue;
+            }
+            let node_score = scores[&node];
+
+            for edge in graph.edges(node) {
+                let next = edge.target();
+                if visited.contains(&next) {
+                    continue;
+                }
+
+                let travel_time = edge.weight().calculate_delay(current_time, overlay, options);
+                if travel_time.is_infinite() {
+                    continue;
+                }
+
+                let next_score = node_score + travel_time;
+                let next_time = current_time + travel_time as u32;
+
+                let improved = scores
+                    .get(&next)
+                    .map_or(true, |&existing| next_score < existing);
+                if !improved {
+                    continue;
+                }
+                scores.insert(next, next_score);
+                predecessors.insert(next, (node, edge.id(), current_time));
+
+                next_candidates
+                    .entry(next)
+                    .and_modify(|(best_score, best_time)| {
+                        if next_score < *best_score {
+                            *best_score = next_score;
+                            *best_time = next_time;
+                        }
+                    })
+                    .or_insert((next_score, next_time));
+            }
+        }
+
+        let mut ranked = DaryHeap::new();
+        for (node, (next_score, next_time)) in next_candidates {
+            ranked.push(MinScored(next_score + heuristic(node), (node, next_time)));
+        }
+
+        frontier = Vec::new();
+        while beam_width.map_or(true, |width| frontier.len() < width) {
+            let Some(MinScored(_, state)) = ranked.pop() else {
+                break;
+            };
+            frontier.push(state);
+        }
+    }
+
+    PathSearch {
+        scores,
+        predecessors,
+    }
 }
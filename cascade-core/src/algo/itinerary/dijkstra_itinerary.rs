@@ -2,17 +2,18 @@ use std::collections::hash_map::Entry::{Occupied, Vacant};
 use std::collections::BinaryHeap;
 
 use ahash::{HashMap, HashMapExt, HashSet, HashSetExt};
-use geo::{line_string, Coord};
-use petgraph::graph::NodeIndex;
+use geo::prelude::*;
+use petgraph::graph::{EdgeIndex, NodeIndex};
 use petgraph::visit::EdgeRef;
 
-use crate::algo::itinerary::segment::{Itinerary, Segment};
+use crate::algo::itinerary::segment::{segment_geometry, Itinerary, Segment};
 use crate::algo::MinScored;
-use crate::graph::TransitGraph;
+use crate::graph::{GraphNode, QueryOptions, TransitGraph};
 use crate::prelude::SnappedPoint;
+use crate::realtime::RealtimeOverlay;
 
-/// Finds the shortest paths from a source node to a target node in a time-dependent graph
-/// using a variation of Dijkstra's algorithm.
+/// Finds the shortest path from a source node to a target node in a time-dependent graph
+/// using an A*-guided variant of time-dependent Dijkstra's algorithm.
 ///
 /// # Arguments
 ///
@@ -32,13 +33,17 @@ use crate::prelude::SnappedPoint;
 ///
 /// # Algorithm Notes
 ///
-/// - This implementation uses a priority queue (`BinaryHeap`) to perform a cost-based search.
+/// - This implementation uses a priority queue (`BinaryHeap`) ordered by `g + h` instead of
+///   the true cost `g`, where `h(n) = haversine_distance(n, target) / graph.max_edge_speed()`
+///   is an admissible lower bound on the remaining travel time — no edge can reach `target`
+///   faster than the straight-line distance divided by the fastest possible mode in the graph.
 /// - The time-dependent properties of edges are computed via `Segment::calculate_itinerary()`,
 ///   which adjusts travel weights based on the current traversal time.
-/// - The algorithm tracks the shortest distances in a `HashMap` (`scores`) and stores predecessors
-///   to reconstruct the final path.
-/// - Geometric information about the path, such as linestrings, is included by deriving it from
-///   the positions of connected nodes.
+/// - The algorithm tracks the shortest distances in a `HashMap` (`scores`), which always holds
+///   the real, un-inflated cost, and stores predecessors to reconstruct the final path.
+/// - Geometric information about the path, such as linestrings, is included via
+///   [`segment_geometry`]: each segment's real `shapes.txt`-derived shape is used when the edge
+///   has one, falling back to a straight line between its endpoint nodes otherwise.
 #[must_use]
 #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
 fn detailed_itinerary_internal(
@@ -46,42 +51,98 @@ fn detailed_itinerary_internal(
     start: NodeIndex,
     target: NodeIndex,
     start_time: u32,
+    wheelchair: bool,
+    max_speed: f64,
+    overlay: Option<&RealtimeOverlay>,
 ) -> Itinerary {
+    detailed_itinerary_constrained(
+        graph,
+        start,
+        target,
+        start_time,
+        wheelchair,
+        max_speed,
+        overlay,
+        &HashSet::new(),
+        &HashSet::new(),
+    )
+    .map_or_else(Itinerary::new, |(itinerary, _)| itinerary)
+}
+
+/// Same search as [`detailed_itinerary_internal`], but edges in `excluded_edges`
+/// and nodes in `excluded_nodes` (other than `start` itself) are never relaxed.
+/// Used by [`k_shortest_itineraries_internal`] to re-run the spur search of
+/// Yen's algorithm over a graph with the previously found paths' shared
+/// prefixes blocked off. Returns both the itinerary and the node sequence it
+/// travels, since Yen's algorithm needs the latter to find spur nodes and to
+/// deduplicate candidates in later rounds.
+#[must_use]
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn detailed_itinerary_constrained(
+    graph: &TransitGraph,
+    start: NodeIndex,
+    target: NodeIndex,
+    start_time: u32,
+    wheelchair: bool,
+    max_speed: f64,
+    overlay: Option<&RealtimeOverlay>,
+    excluded_edges: &HashSet<EdgeIndex>,
+    excluded_nodes: &HashSet<NodeIndex>,
+) -> Option<(Itinerary, Vec<NodeIndex>)> {
+    let options = QueryOptions {
+        wheelchair,
+        ..QueryOptions::default()
+    };
+
+    let heuristic = |node: NodeIndex| -> f64 {
+        let (Some(node_geometry), Some(target_geometry)) = (
+            graph.node_weight(node).map(GraphNode::geometry),
+            graph.node_weight(target).map(GraphNode::geometry),
+        ) else {
+            return 0.0;
+        };
+        node_geometry.haversine_distance(target_geometry) / max_speed
+    };
+
     let mut visited = HashSet::new();
     let mut scores: HashMap<NodeIndex, f64> =
         HashMap::with_capacity(graph.into_inner_graph().node_count());
 
     let mut visit_next = BinaryHeap::new();
     scores.insert(start, 0.0);
-    visit_next.push(MinScored(0.0, (start, start_time)));
+    visit_next.push(MinScored(heuristic(start), (start, start_time)));
 
     let mut predecessors: HashMap<NodeIndex, (NodeIndex, Segment)> = HashMap::new();
 
-    while let Some(MinScored(node_score, (node, current_time))) = visit_next.pop() {
+    while let Some(MinScored(_, (node, current_time))) = visit_next.pop() {
         if visited.contains(&node) {
             continue;
         }
 
+        let node_score = scores[&node];
+
         if node == target {
             break;
         }
 
         for edge in graph.edges(node) {
-            let current_node = edge.source();
             let next_node = edge.target();
 
-            let edge_geometry = line_string![
-                Coord::from(*graph.node_weight(current_node).unwrap().geometry()),
-                Coord::from(*graph.node_weight(next_node).unwrap().geometry())
-            ];
-
-            if visited.contains(&next_node) {
+            if visited.contains(&next_node)
+                || excluded_edges.contains(&edge.id())
+                || (next_node != start && excluded_nodes.contains(&next_node))
+            {
                 continue;
             }
 
-            let segment = edge
-                .weight()
-                .calculate_itinerary(current_time, edge_geometry);
+            let geometry = segment_geometry(graph, edge.weight().geometry(), node, next_node);
+
+            let segment = edge.weight().calculate_itinerary(
+                current_time,
+                geometry,
+                &options,
+                overlay,
+            );
 
             if matches!(segment, Segment::NoService) {
                 continue;
@@ -96,13 +157,19 @@ fn detailed_itinerary_internal(
                 Occupied(mut ent) => {
                     if next_score < *ent.get() {
                         ent.insert(next_score);
-                        visit_next.push(MinScored(next_score, (next_node, next_time)));
+                        visit_next.push(MinScored(
+                            next_score + heuristic(next_node),
+                            (next_node, next_time),
+                        ));
                         predecessors.insert(next_node, (node, segment));
                     }
                 }
                 Vacant(ent) => {
                     ent.insert(next_score);
-                    visit_next.push(MinScored(next_score, (next_node, next_time)));
+                    visit_next.push(MinScored(
+                        next_score + heuristic(next_node),
+                        (next_node, next_time),
+                    ));
                     predecessors.insert(next_node, (node, segment));
                 }
             }
@@ -112,20 +179,23 @@ fn detailed_itinerary_internal(
 
     // Reconstruct the path
 
-    if scores.contains_key(&target) {
-        let mut itinerary = Itinerary::new();
-        let mut current_node = target;
+    if !scores.contains_key(&target) {
+        return None;
+    }
 
-        while let Some((prev_node, segment)) = predecessors.get(&current_node) {
-            itinerary.push(segment.clone());
-            current_node = *prev_node;
-        }
+    let mut itinerary = Itinerary::new();
+    let mut nodes = vec![target];
+    let mut current_node = target;
 
-        itinerary.travel.reverse();
-        return itinerary;
+    while let Some((prev_node, segment)) = predecessors.get(&current_node) {
+        itinerary.push(segment.clone());
+        current_node = *prev_node;
+        nodes.push(current_node);
     }
 
-    Itinerary::new()
+    itinerary.segments.reverse();
+    nodes.reverse();
+    Some((itinerary, nodes))
 }
 
 /// Computes an itinerary between two locations in a transit graph.
@@ -136,6 +206,8 @@ fn detailed_itinerary_internal(
 /// * `start` - The starting point, represented as a `SnappedPoint` (snap-matched to a graph node).
 /// * `target` - The target point, represented as a `SnappedPoint` (snap-matched to a graph node).
 /// * `start_time` - The starting time in seconds since midnight.
+/// * `overlay` - An optional [`RealtimeOverlay`] whose live delays and
+///   cancellations are consulted instead of the static schedule alone.
 ///
 /// # Returns
 ///
@@ -150,8 +222,383 @@ pub fn detailed_itinerary<'a, 'b>(
     start: &'b SnappedPoint,
     target: &'b SnappedPoint,
     start_time: u32,
+    wheelchair: bool,
+    overlay: Option<&RealtimeOverlay>,
+) -> Itinerary<'a> {
+    detailed_itinerary_internal(
+        graph,
+        *start.index(),
+        *target.index(),
+        start_time,
+        wheelchair,
+        graph.max_edge_speed(),
+        overlay,
+    )
+}
+
+/// Same search as [`detailed_itinerary`], but `max_speed` (in meters/second)
+/// is supplied by the caller instead of being derived automatically from
+/// [`TransitGraph::max_edge_speed`]. The heuristic stays admissible and the
+/// search stays exact as long as `max_speed` is at least as large as the
+/// fastest mode actually present in `graph` — a caller-supplied upper bound
+/// (e.g. a known regional rail top speed) can tighten the heuristic further
+/// than the graph-wide default when the network's fastest edge is an outlier
+/// that rarely lies on the route between `start` and `target`. Passing a
+/// `max_speed` that's too low makes the heuristic inadmissible, trading
+/// optimality away for additional pruning — [`shortest_path_itinerary`](crate::algo::shortest_path_itinerary)
+/// remains available, unguided, for when no heuristic at all is wanted.
+#[must_use]
+pub fn detailed_itinerary_astar<'a, 'b>(
+    graph: &'a TransitGraph,
+    start: &'b SnappedPoint,
+    target: &'b SnappedPoint,
+    start_time: u32,
+    wheelchair: bool,
+    max_speed: f64,
+    overlay: Option<&RealtimeOverlay>,
 ) -> Itinerary<'a> {
-    let result = detailed_itinerary_internal(graph, *start.index(), *target.index(), start_time);
+    detailed_itinerary_internal(
+        graph,
+        *start.index(),
+        *target.index(),
+        start_time,
+        wheelchair,
+        max_speed,
+        overlay,
+    )
+}
+
+/// Finds up to `k` loopless itineraries from `start` to `target`, in
+/// increasing total weight, using Yen's algorithm built on top of
+/// [`detailed_itinerary_constrained`].
+///
+/// `A` holds the itineraries found so far (`A[0]` is the plain shortest
+/// itinerary). Each subsequent round walks every spur node along the
+/// previous itinerary, blocks off the edges and root-prefix nodes that
+/// would just recreate an already-found path sharing that root, and
+/// re-searches from the spur node at the arrival time implied by the root
+/// prefix's accumulated weight. Every candidate produced this way is pushed
+/// onto `B`, a min-heap keyed by total weight that persists across rounds;
+/// each round pops `B`'s best not-yet-found candidate into `A`. The loop
+/// stops once `k` itineraries are found or `B` runs dry.
+#[must_use]
+fn k_shortest_itineraries_internal(
+    graph: &TransitGraph,
+    start: NodeIndex,
+    target: NodeIndex,
+    start_time: u32,
+    k: usize,
+    wheelchair: bool,
+    overlay: Option<&RealtimeOverlay>,
+) -> Vec<Itinerary> {
+    let Some(first) = detailed_itinerary_constrained(
+        graph,
+        start,
+        target,
+        start_time,
+        wheelchair,
+        graph.max_edge_speed(),
+        overlay,
+        &HashSet::new(),
+        &HashSet::new(),
+    ) else {
+        return Vec::new();
+    };
+
+    let mut found = vec![first];
+    let mut seen_paths: HashSet<Vec<NodeIndex>> = HashSet::new();
+    seen_paths.insert(found[0].1.clone());
+
+    let mut candidates: Vec<Option<(Itinerary, Vec<NodeIndex>)>> = Vec::new();
+    let mut candidate_queue: BinaryHeap<MinScored<usize>> = BinaryHeap::new();
+
+    while found.len() < k {
+        let (prev_itinerary, prev_nodes) = &found[found.len() - 1];
+
+        for spur_index in 0..prev_nodes.len().saturating_sub(1) {
+            let spur_node = prev_nodes[spur_index];
+            let root_nodes = &prev_nodes[..=spur_index];
+
+            // Block the edge that would recreate any previously found path
+            // sharing this exact root prefix.
+            let excluded_edges: HashSet<EdgeIndex> = found
+                .iter()
+                .filter(|(_, nodes)| {
+                    nodes.len() > spur_index + 1 && nodes[..=spur_index] == *root_nodes
+                })
+                .filter_map(|(_, nodes)| graph.find_edge(nodes[spur_index], nodes[spur_index + 1]))
+                .collect();
+
+            // The root prefix's nodes (other than the spur itself) may not
+            // be revisited, or the spur search could loop back through them.
+            let excluded_nodes: HashSet<NodeIndex> =
+                root_nodes[..spur_index].iter().copied().collect();
+
+            // Arrival time at the spur node, recomputed from the root
+            // prefix's accumulated weight so the spur search evaluates
+            // time-dependent schedules at the right moment.
+            let root_weight: f64 = prev_itinerary.segments[..spur_index]
+                .iter()
+                .map(Segment::weight)
+                .sum();
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let spur_time = start_time + root_weight as u32;
+
+            let Some((spur_itinerary, spur_nodes)) = detailed_itinerary_constrained(
+                graph,
+                spur_node,
+                target,
+                spur_time,
+                wheelchair,
+                graph.max_edge_speed(),
+                overlay,
+                &excluded_edges,
+                &excluded_nodes,
+            ) else {
+                continue;
+            };
+
+            let mut candidate_nodes = root_nodes[..spur_index].to_vec();
+            candidate_nodes.extend(spur_nodes);
+
+            if !seen_paths.insert(candidate_nodes.clone()) {
+                continue;
+            }
+
+            let mut candidate_itinerary = Itinerary::new();
+            for segment in &prev_itinerary.segments[..spur_index] {
+                candidate_itinerary.push(segment.clone());
+            }
+            for segment in spur_itinerary.segments {
+                candidate_itinerary.push(segment);
+            }
+
+            let weight = candidate_itinerary.duration();
+            candidates.push(Some((candidate_itinerary, candidate_nodes)));
+            candidate_queue.push(MinScored(weight, candidates.len() - 1));
+        }
+
+        let Some(MinScored(_, index)) = candidate_queue.pop() else {
+            break;
+        };
+
+        let Some(best) = candidates[index].take() else {
+            continue;
+        };
+        found.push(best);
+    }
+
+    found.into_iter().map(|(itinerary, _)| itinerary).collect()
+}
+
+/// Finds up to `k` loopless itineraries between two locations in a transit
+/// graph, in increasing total weight, using Yen's algorithm.
+///
+/// # Arguments
+///
+/// * `graph` - A reference to a `TransitGraph` object, representing the transit network.
+/// * `start` - The starting point, represented as a `SnappedPoint` (snap-matched to a graph node).
+/// * `target` - The target point, represented as a `SnappedPoint` (snap-matched to a graph node).
+/// * `start_time` - The starting time in seconds since midnight.
+/// * `k` - The maximum number of itineraries to return.
+/// * `overlay` - An optional [`RealtimeOverlay`] whose live delays and
+///   cancellations are consulted instead of the static schedule alone.
+///
+/// # Returns
+///
+/// Up to `k` itineraries in increasing total weight, starting with the plain
+/// shortest itinerary. Fewer than `k` are returned if that many loopless
+/// alternatives don't exist; an empty `Vec` is returned if `start` cannot
+/// reach `target` at all.
+#[must_use]
+pub fn k_shortest_itineraries<'a, 'b>(
+    graph: &'a TransitGraph,
+    start: &'b SnappedPoint,
+    target: &'b SnappedPoint,
+    start_time: u32,
+    k: usize,
+    wheelchair: bool,
+    overlay: Option<&RealtimeOverlay>,
+) -> Vec<Itinerary<'a>> {
+    k_shortest_itineraries_internal(
+        graph,
+        *start.index(),
+        *target.index(),
+        start_time,
+        k,
+        wheelchair,
+        overlay,
+    )
+}
+
+/// Finds every co-optimal itinerary from `start` to `target` — all loopless
+/// paths that share the single optimal arrival time, rather than [`k_shortest_itineraries`]'s
+/// `k` cheapest-but-not-necessarily-tied alternatives.
+///
+/// First runs an uninformed [`time_dependent_dijkstra`](crate::algo::dijkstra::time_dependent_dijkstra)
+/// from `start` to get the optimal cost `dist[v]` to every node. Since `dist[v]` is an
+/// elapsed duration rather than a clock time, every optimal path reaches `v` at the same
+/// clock `start_time + dist[v]` regardless of which path was taken, so the edges that
+/// belong to *some* optimal path can be found in one pass: edge `(u, v)` belongs to the
+/// optimality DAG exactly when `dist[u] + delay(u, v, start_time + dist[u])` equals
+/// `dist[v]`, within floating-point tolerance. Every `start -> target` path through that
+/// DAG is then a co-optimal itinerary, enumerated by bounded DFS.
+///
+/// Returns at most `limit` itineraries — the DAG can have exponentially many source-target
+/// paths, so enumeration stops early rather than exhausting all of them. Returns an empty
+/// `Vec` if `target` is unreachable from `start`.
+#[must_use]
+pub fn all_optimal_itineraries<'a, 'b>(
+    graph: &'a TransitGraph,
+    start: &'b SnappedPoint,
+    target: &'b SnappedPoint,
+    start_time: u32,
+    wheelchair: bool,
+    overlay: Option<&RealtimeOverlay>,
+    limit: usize,
+) -> Vec<Itinerary<'a>> {
+    all_optimal_itineraries_internal(
+        graph,
+        *start.index(),
+        *target.index(),
+        start_time,
+        wheelchair,
+        overlay,
+        limit,
+    )
+}
+
+#[must_use]
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn all_optimal_itineraries_internal(
+    graph: &TransitGraph,
+    start: NodeIndex,
+    target: NodeIndex,
+    start_time: u32,
+    wheelchair: bool,
+    overlay: Option<&RealtimeOverlay>,
+    limit: usize,
+) -> Vec<Itinerary> {
+    let options = QueryOptions {
+        wheelchair,
+        ..QueryOptions::default()
+    };
+
+    let search = crate::algo::dijkstra::time_dependent_dijkstra(
+        graph,
+        start,
+        None,
+        start_time,
+        overlay,
+        &options,
+    );
+
+    if !search.scores.contains_key(&target) {
+        return Vec::new();
+    }
+
+    // Every edge (u, v) that lies on *some* optimal path: relaxing it from
+    // u's optimal cost reproduces v's optimal cost exactly.
+    let mut dag_predecessors: HashMap<NodeIndex, Vec<(NodeIndex, EdgeIndex)>> = HashMap::new();
+    for (&u, &dist_u) in &search.scores {
+        let clock_at_u = start_time + dist_u as u32;
+        for edge in graph.edges(u) {
+            let v = edge.target();
+            let Some(&dist_v) = search.scores.get(&v) else {
+                continue;
+            };
+
+            let delay = edge.weight().calculate_delay(clock_at_u, overlay, &options);
+            if delay.is_infinite() {
+                continue;
+            }
+
+            if (dist_u + delay - dist_v).abs() < 1e-6 {
+                dag_predecessors.entry(v).or_default().push((u, edge.id()));
+            }
+        }
+    }
+
+    let paths = enumerate_dag_paths(start, target, &dag_predecessors, limit);
+
+    paths
+        .into_iter()
+        .map(|path| build_itinerary_from_path(graph, start_time, &options, overlay, &path))
+        .collect()
+}
+
+/// Enumerates up to `limit` `start -> target` paths through the optimality DAG built by
+/// [`all_optimal_itineraries_internal`], via bounded depth-first search backward from
+/// `target`. Each returned path is the forward sequence of `(node, edge)` pairs describing
+/// the edge arriving at `node`, from the first hop after `start` through `target`.
+fn enumerate_dag_paths(
+    start: NodeIndex,
+    target: NodeIndex,
+    dag_predecessors: &HashMap<NodeIndex, Vec<(NodeIndex, EdgeIndex)>>,
+    limit: usize,
+) -> Vec<Vec<(NodeIndex, EdgeIndex)>> {
+    let mut results = Vec::new();
+    let mut path = Vec::new();
+
+    fn dfs(
+        node: NodeIndex,
+        start: NodeIndex,
+        path: &mut Vec<(NodeIndex, EdgeIndex)>,
+        dag_predecessors: &HashMap<NodeIndex, Vec<(NodeIndex, EdgeIndex)>>,
+        results: &mut Vec<Vec<(NodeIndex, EdgeIndex)>>,
+        limit: usize,
+    ) {
+        if results.len() >= limit {
+            return;
+        }
+        if node == start {
+            results.push(path.iter().rev().copied().collect());
+            return;
+        }
+        let Some(predecessors) = dag_predecessors.get(&node) else {
+            return;
+        };
+        for &(prev, edge) in predecessors {
+            if results.len() >= limit {
+                return;
+            }
+            path.push((node, edge));
+            dfs(prev, start, path, dag_predecessors, results, limit);
+            path.pop();
+        }
+    }
+
+    dfs(target, start, &mut path, dag_predecessors, &mut results, limit);
+    results
+}
+
+/// Replays `path`'s edges forward from `start_time`, turning it into a full [`Itinerary`]
+/// via [`GraphEdge::calculate_itinerary`] at each edge's actual traversal clock.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn build_itinerary_from_path<'a>(
+    graph: &'a TransitGraph,
+    start_time: u32,
+    options: &QueryOptions,
+    overlay: Option<&RealtimeOverlay>,
+    path: &[(NodeIndex, EdgeIndex)],
+) -> Itinerary<'a> {
+    let mut itinerary = Itinerary::new();
+    let mut current_time = start_time;
+
+    for &(node, edge_id) in path {
+        let Some(edge_weight) = graph.edge_weight(edge_id) else {
+            continue;
+        };
+        let Some((source, target)) = graph.into_inner_graph().edge_endpoints(edge_id) else {
+            continue;
+        };
+        debug_assert_eq!(target, node);
+
+        let geometry = segment_geometry(graph, edge_weight.geometry(), source, target);
+        let segment = edge_weight.calculate_itinerary(current_time, geometry, options, overlay);
+        current_time += segment.weight() as u32;
+        itinerary.push(segment);
+    }
 
-    result
+    itinerary
 }
@@ -0,0 +1,8 @@
+pub mod segment;
+
+mod dijkstra_itinerary;
+
+pub use dijkstra_itinerary::{
+    all_optimal_itineraries, detailed_itinerary, detailed_itinerary_astar, k_shortest_itineraries,
+};
+pub use segment::{Itinerary, Segment};
@@ -1,29 +1,57 @@
+use std::borrow::Cow;
+
 use geo::LineString;
 use geojson::{Feature, FeatureCollection, Geometry};
+use petgraph::graph::NodeIndex;
 use serde_json::{json, map::Map};
 
-use crate::graph::{GraphEdge, Trip};
+use crate::graph::{GraphEdge, QueryOptions, Trip, TransitGraph};
+use crate::interner::RouteId;
+use crate::realtime::RealtimeOverlay;
+
+/// Returns `edge_geometry` borrowed as-is when the edge carries a real
+/// `shapes.txt`-derived shape, or else synthesizes a straight two-point line
+/// between `source` and `target`'s node coordinates, so a segment's geometry
+/// falls back to a straight line rather than going missing entirely. Returns
+/// `None` only if `source` or `target` isn't a valid node in `graph`.
+pub(crate) fn segment_geometry<'a>(
+    graph: &'a TransitGraph,
+    edge_geometry: Option<&'a LineString>,
+    source: NodeIndex,
+    target: NodeIndex,
+) -> Option<Cow<'a, LineString>> {
+    if let Some(geometry) = edge_geometry {
+        return Some(Cow::Borrowed(geometry));
+    }
+
+    let source_point = *graph.node_weight(source)?.geometry();
+    let target_point = *graph.node_weight(target)?.geometry();
+    Some(Cow::Owned(LineString::from(vec![source_point, target_point])))
+}
 
 impl GraphEdge {
     pub(crate) fn calculate_itinerary<'a>(
         &'a self,
         current_time: u32,
-        geometry: Option<&'a LineString>,
-        wheelchair: bool,
+        geometry: Option<Cow<'a, LineString>>,
+        options: &QueryOptions,
+        overlay: Option<&RealtimeOverlay>,
     ) -> Segment<'a> {
         match self {
             Self::Transit(transit_edge) => {
-                let trips = &transit_edge.edge_trips;
-                match trips.binary_search_by(|trip| trip.departure_time.cmp(&current_time)) {
-                    Ok(index) | Err(index) if index < trips.len() => {
-                        let trip = &trips[index];
+                // Skip boarding here entirely if the stop itself rules out wheelchair access
+                if options.wheelchair && !transit_edge.boarding_wheelchair_accessible {
+                    return Segment::NoService;
+                }
 
+                match transit_edge.next_trip(current_time, overlay) {
+                    Some((trip, arrival_time)) => {
                         // Skip trips that are not wheelchair accessible if wheelchair is required
-                        if !trip.wheelchair_accessible && wheelchair {
+                        if options.wheelchair && !trip.wheelchair_accessible {
                             return Segment::NoService;
                         }
 
-                        let weight = f64::from(trip.arrival_time - current_time);
+                        let weight = f64::from(arrival_time - current_time);
 
                         Segment::Transit {
                             trip,
@@ -31,14 +59,20 @@ impl GraphEdge {
                             geometry,
                         }
                     }
-                    // No trip found after current time, skip this edge
-                    _ => Segment::NoService,
+                    // No trip found today or on the next service day, skip this edge
+                    None => Segment::NoService,
+                }
+            }
+            Self::Transfer(walk_edge) | Self::Walk(walk_edge) => {
+                if walk_edge.edge_weight * crate::WALK_SPEED > options.max_walk_distance {
+                    return Segment::NoService;
+                }
+
+                Segment::Pedestrian {
+                    weight: walk_edge.edge_weight,
+                    geometry,
                 }
             }
-            Self::Transfer(walk_edge) | Self::Walk(walk_edge) => Segment::Pedestrian {
-                weight: walk_edge.edge_weight,
-                geometry,
-            },
         }
     }
 }
@@ -48,11 +82,11 @@ pub enum Segment<'a> {
     Transit {
         trip: &'a Trip,
         weight: f64,
-        geometry: Option<&'a LineString>,
+        geometry: Option<Cow<'a, LineString>>,
     },
     Pedestrian {
         weight: f64,
-        geometry: Option<&'a LineString>,
+        geometry: Option<Cow<'a, LineString>>,
     },
     NoService,
 }
@@ -88,7 +122,7 @@ impl<'a> Itinerary<'a> {
         self.segments.iter().map(Segment::weight).sum()
     }
 
-    pub fn to_geojson(&self) -> geojson::GeoJson {
+    pub fn to_geojson(&self, graph: &TransitGraph) -> geojson::GeoJson {
         let mut features = vec![];
 
         for (i, segment) in self.segments.iter().enumerate() {
@@ -103,7 +137,10 @@ impl<'a> Itinerary<'a> {
                     properties.insert("sequence".to_string(), i.into());
                     properties.insert("type".to_string(), "Transit".into());
                     properties.insert("weight".to_string(), weight.to_string().into());
-                    properties.insert("route_id".to_string(), trip.route_id.clone().into());
+                    properties.insert(
+                        "route_id".to_string(),
+                        graph.route_id_str(trip.route_id).into(),
+                    );
                     properties.insert("departure_time".to_string(), trip.departure_time.into());
                     properties.insert("arrival_time".to_string(), trip.arrival_time.into());
                     properties.insert(
@@ -111,8 +148,28 @@ impl<'a> Itinerary<'a> {
                         trip.wheelchair_accessible.into(),
                     );
 
+                    let route_meta = graph.route_meta(trip.route_id);
+                    properties.insert(
+                        "route_short_name".to_string(),
+                        route_meta.route_short_name.clone().into(),
+                    );
+                    properties.insert(
+                        "route_color".to_string(),
+                        route_meta.route_color.clone().into(),
+                    );
+                    properties.insert(
+                        "route_text_color".to_string(),
+                        route_meta.route_text_color.clone().into(),
+                    );
+                    properties.insert(
+                        "trip_headsign".to_string(),
+                        trip.trip_headsign
+                            .map(|id| graph.headsign_str(id))
+                            .into(),
+                    );
+
                     features.push(Feature {
-                        geometry: geometry.map(|g| Geometry::new(g.into())),
+                        geometry: geometry.as_ref().map(|g| Geometry::new(g.as_ref().into())),
                         properties: Some(properties),
                         id: None,
                         bbox: None,
@@ -126,7 +183,7 @@ impl<'a> Itinerary<'a> {
                     properties.insert("weight".to_string(), json!(weight));
 
                     features.push(Feature {
-                        geometry: geometry.map(|g| Geometry::new(g.into())),
+                        geometry: geometry.as_ref().map(|g| Geometry::new(g.as_ref().into())),
                         properties: Some(properties),
                         id: None,
                         bbox: None,
@@ -147,16 +204,22 @@ impl<'a> Itinerary<'a> {
 
 #[cfg(test)]
 mod tests {
+    use petgraph::graph::DiGraph;
+    use rstar::RTree;
+
     use super::*;
-    use crate::graph::{TransitEdge, WalkEdge};
+    use crate::graph::{TransitEdge, TransitGraph, WalkEdge};
+    use crate::interner::{StopId, TripId};
 
     #[test]
     fn test_itinerary_duration() {
         let trip = Trip {
-            route_id: "route_1".to_string(),
+            route_id: RouteId(0),
             departure_time: 1000,
             arrival_time: 1100,
             wheelchair_accessible: true,
+            trip_headsign: None,
+            trip_id: TripId(0),
         };
 
         let segment1 = Segment::Transit {
@@ -181,10 +244,12 @@ mod tests {
     #[test]
     fn test_itinerary_to_geojson() {
         let trip = Trip {
-            route_id: "route_1".to_string(),
+            route_id: RouteId(0),
             departure_time: 1000,
             arrival_time: 1100,
             wheelchair_accessible: true,
+            trip_headsign: None,
+            trip_id: TripId(0),
         };
 
         let segment1 = Segment::Transit {
@@ -202,7 +267,11 @@ mod tests {
         itinerary.push(segment1);
         itinerary.push(segment2);
 
-        let geojson = itinerary.to_geojson();
+        let mut graph = TransitGraph::from_parts(DiGraph::new(), RTree::new());
+        graph
+            .interners_mut()
+            .intern_route("route_1", crate::interner::RouteMeta::default);
+        let geojson = itinerary.to_geojson(&graph);
         if let geojson::GeoJson::FeatureCollection(fc) = geojson {
             assert_eq!(fc.features.len(), 2);
             assert_eq!(
@@ -221,18 +290,23 @@ mod tests {
     #[test]
     fn test_calculate_itinerary_transit() {
         let trip = Trip {
-            route_id: "route_1".to_string(),
+            route_id: RouteId(0),
             departure_time: 1000,
             arrival_time: 1100,
             wheelchair_accessible: true,
+            trip_headsign: None,
+            trip_id: TripId(0),
         };
 
         let transit_edge = GraphEdge::Transit(TransitEdge {
             edge_trips: vec![trip],
+            source_stop: StopId(0),
+            target_stop: StopId(1),
             geometry: None,
+            boarding_wheelchair_accessible: true,
         });
 
-        let segment = transit_edge.calculate_itinerary(900, None, false);
+        let segment = transit_edge.calculate_itinerary(900, None, &QueryOptions::default(), None);
         if let Segment::Transit { weight, .. } = segment {
             let tolerance = 1e-6;
             assert!((weight - 200.0).abs() < tolerance);
@@ -244,22 +318,29 @@ mod tests {
     #[test]
     fn test_calculate_itinerary_no_service() {
         let trip = Trip {
-            route_id: "route_1".to_string(),
+            route_id: RouteId(0),
             departure_time: 1000,
             arrival_time: 1100,
             wheelchair_accessible: false,
+            trip_headsign: None,
+            trip_id: TripId(0),
         };
 
         let transit_edge = GraphEdge::Transit(TransitEdge {
             edge_trips: vec![trip],
+            source_stop: StopId(0),
+            target_stop: StopId(1),
             geometry: None,
+            boarding_wheelchair_accessible: true,
         });
 
-        let segment = transit_edge.calculate_itinerary(900, None, true);
+        let segment = transit_edge.calculate_itinerary(900, None, &QueryOptions { wheelchair: true, ..QueryOptions::default() }, None);
         assert!(matches!(segment, Segment::NoService));
 
-        let segment = transit_edge.calculate_itinerary(1500, None, false);
-        assert!(matches!(segment, Segment::NoService));
+        // Past the only trip of the day, the search wraps to the next service
+        // day's trip: arrival 1100 + 86400, boarded from 1500.
+        let segment = transit_edge.calculate_itinerary(1500, None, &QueryOptions::default(), None);
+        assert!(matches!(segment, Segment::Transit { weight, .. } if (weight - 86000.0).abs() < 1e-6));
     }
 
     #[test]
@@ -269,7 +350,7 @@ mod tests {
             geometry: None,
         });
 
-        let segment = walk_edge.calculate_itinerary(0, None, false);
+        let segment = walk_edge.calculate_itinerary(0, None, &QueryOptions::default(), None);
         if let Segment::Pedestrian { weight, .. } = segment {
             let tolerance = 1e-6;
             assert!((weight - 50.0).abs() < tolerance);
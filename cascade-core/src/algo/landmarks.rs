@@ -0,0 +1,301 @@
+//! Landmark-anchor distance cache for fast repeated OD/isochrone queries.
+//!
+//! Re-running a full [`time_dependent_dijkstra`] per origin dominates
+//! runtime for dashboards that repeatedly query the same graph at the same
+//! departure window. [`TransitGraph::build_cache`] precomputes a full
+//! shortest-path tree from each of a fixed set of anchor nodes once, and
+//! returns it as a serializable [`LandmarkCache`] that can be written to
+//! disk and reused across many queries. A fresh query point is then
+//! answered by a bounded local search out to its nearest cached anchor,
+//! combined with that anchor's precomputed tree, instead of a fresh global
+//! search — the same "precomputed route tree plus local search" shape used
+//! by landmark-based long-range route planners.
+
+use hashbrown::{HashMap, HashSet};
+use petgraph::graph::NodeIndex;
+use petgraph::visit::EdgeRef;
+use serde::{Deserialize, Serialize};
+
+use crate::algo::dary_heap::DaryHeap;
+use crate::algo::dijkstra::time_dependent_dijkstra;
+use crate::algo::MinScored;
+use crate::graph::{QueryOptions, TransitGraph};
+use crate::prelude::SnappedPoint;
+use crate::realtime::RealtimeOverlay;
+
+/// Bound on how many nodes [`LandmarkCache`]'s local search settles before
+/// giving up on finding a cached anchor, so a query point nowhere near any
+/// anchor fails fast instead of degrading into a full graph traversal.
+const MAX_LOCAL_EXPANSIONS: usize = 5_000;
+
+/// Precomputed shortest-path trees from a fixed set of anchor nodes at one
+/// `dep_time`, built by [`TransitGraph::build_cache`].
+///
+/// Distances are exact only from an anchor; [`Self::distance`] and
+/// [`Self::scores_from`] route a query point to its nearest cached anchor
+/// with a bounded local search and add that local hop to the anchor's
+/// precomputed tree, trading a small amount of accuracy for skipping a
+/// fresh global search. The cache is only valid for the `graph` it was
+/// built from and the `dep_time` it was built at.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LandmarkCache {
+    dep_time: u32,
+    anchors: Vec<NodeIndex>,
+    trees: Vec<HashMap<NodeIndex, f64>>,
+}
+
+impl LandmarkCache {
+    /// The departure time this cache was built for.
+    #[must_use]
+    pub const fn dep_time(&self) -> u32 {
+        self.dep_time
+    }
+
+    /// Approximate shortest-path weight from `source` to `target`, in
+    /// seconds, via whichever cached anchor is nearest to `source`.
+    ///
+    /// Returns `None` if no anchor is reachable from `source` within
+    /// [`MAX_LOCAL_EXPANSIONS`] settled nodes, or `target` isn't in that
+    /// anchor's precomputed tree.
+    #[must_use]
+    pub fn distance(
+        &self,
+        graph: &TransitGraph,
+        source: &SnappedPoint,
+        target: &SnappedPoint,
+        overlay: Option<&RealtimeOverlay>,
+    ) -> Option<f64> {
+        let options = QueryOptions::default();
+        let (anchor, local_weight) =
+            self.nearest_anchor(graph, *source.index(), overlay, &options)?;
+        let tree_weight = self.trees[anchor].get(target.index())?;
+
+        Some(local_weight + tree_weight + *source.distance() + *target.distance())
+    }
+
+    /// Approximate shortest-path weight from `source` to every node in its
+    /// nearest cached anchor's tree, in seconds — a drop-in replacement for
+    /// [`crate::algo::single_source_shortest_path_weight`]'s return shape,
+    /// usable directly by [`crate::algo::isochrone`].
+    ///
+    /// Returns an empty map if no anchor is reachable from `source` within
+    /// [`MAX_LOCAL_EXPANSIONS`] settled nodes.
+    #[must_use]
+    pub fn scores_from(
+        &self,
+        graph: &TransitGraph,
+        source: &SnappedPoint,
+        overlay: Option<&RealtimeOverlay>,
+    ) -> HashMap<NodeIndex, f64> {
+        let options = QueryOptions::default();
+        let Some((anchor, local_weight)) =
+            self.nearest_anchor(graph, *source.index(), overlay, &options)
+        else {
+            return HashMap::new();
+        };
+
+        let distance = *source.distance();
+        self.trees[anchor]
+            .iter()
+            .map(|(&node, &weight)| (node, weight + local_weight + distance))
+            .collect()
+    }
+
+    /// Bounded best-first search from `start` out to the nearest node that's
+    /// one of this cache's anchors. Stops as soon as an anchor is settled,
+    /// or gives up once [`MAX_LOCAL_EXPANSIONS`] nodes have been visited
+    /// without finding one. Returns the anchor's position in
+    /// [`Self::trees`] and the local weight reaching it.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn nearest_anchor(
+        &self,
+        graph: &TransitGraph,
+        start: NodeIndex,
+        overlay: Option<&RealtimeOverlay>,
+        options: &QueryOptions,
+    ) -> Option<(usize, f64)> {
+        let anchor_positions: HashMap<NodeIndex, usize> = self
+            .anchors
+            .iter()
+            .enumerate()
+            .map(|(position, &node)| (node, position))
+            .collect();
+
+        if let Some(&position) = anchor_positions.get(&start) {
+            return Some((position, 0.0));
+        }
+
+        let mut visited = HashSet::new();
+        let mut visit_next = DaryHeap::new();
+        visit_next.push(MinScored(0.0, (start, self.dep_time)));
+
+        while let Some(MinScored(weight, (node, time))) = visit_next.pop() {
+            if !visited.insert(node) {
+                continue;
+            }
+            if let Some(&position) = anchor_positions.get(&node) {
+                return Some((position, weight));
+            }
+            if visited.len() > MAX_LOCAL_EXPANSIONS {
+                return None;
+            }
+
+            for edge in graph.edges(node) {
+                let next = edge.target();
+                if visited.contains(&next) {
+                    continue;
+                }
+
+                let travel_time = edge.weight().calculate_delay(time, overlay, options);
+                if travel_time.is_infinite() {
+                    continue;
+                }
+
+                let next_time = time + travel_time as u32;
+                visit_next.push(MinScored(weight + travel_time, (next, next_time)));
+            }
+        }
+
+        None
+    }
+}
+
+impl TransitGraph {
+    /// Builds a [`LandmarkCache`]: a full [`time_dependent_dijkstra`] tree
+    /// from every point in `anchors`, at `dep_time`. The result is a plain
+    /// serde-serializable value, so it can be written to disk (e.g. with
+    /// `bincode`, like [`crate::cache`]'s graph cache) and reloaded for
+    /// later runs as long as `self` and `dep_time` haven't changed.
+    #[must_use]
+    pub fn build_cache(
+        &self,
+        anchors: &[SnappedPoint],
+        dep_time: u32,
+        overlay: Option<&RealtimeOverlay>,
+    ) -> LandmarkCache {
+        let options = QueryOptions::default();
+
+        let trees = anchors
+            .iter()
+            .map(|anchor| {
+                time_dependent_dijkstra(self, *anchor.index(), None, dep_time, overlay, &options).scores
+            })
+            .collect();
+
+        LandmarkCache {
+            dep_time,
+            anchors: anchors.iter().map(|anchor| *anchor.index()).collect(),
+            trees,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::Point;
+    use petgraph::graph::DiGraph;
+    use rstar::RTree;
+
+    use super::*;
+    use crate::connectors::build_rtree;
+    use crate::graph::{GraphEdge, GraphNode, WalkEdge, WalkNode};
+    use osm4routing::NodeId;
+
+    /// Builds a small chain graph `a <-> b <-> c <-> d`, each hop a 10-second
+    /// walk edge traversable in either direction, and returns the graph plus
+    /// a `SnappedPoint` for every node (each point sits exactly on its node,
+    /// so snapping adds no extra distance).
+    fn chain_graph() -> (TransitGraph, Vec<SnappedPoint>) {
+        let mut graph = DiGraph::<GraphNode, GraphEdge>::new();
+
+        let points = [
+            Point::new(0.0, 0.0),
+            Point::new(0.0, 1.0),
+            Point::new(0.0, 2.0),
+            Point::new(0.0, 3.0),
+        ];
+
+        let nodes: Vec<_> = points
+            .iter()
+            .map(|&geometry| {
+                graph.add_node(GraphNode::Walk(WalkNode {
+                    id: NodeId(0),
+                    geometry,
+                }))
+            })
+            .collect();
+
+        for pair in nodes.windows(2) {
+            let edge = GraphEdge::Walk(WalkEdge {
+                edge_weight: 10.0,
+                geometry: None,
+            });
+            graph.add_edge(pair[0], pair[1], edge.clone());
+            graph.add_edge(pair[1], pair[0], edge);
+        }
+
+        let rtree = build_rtree(&graph);
+        let mut transit_graph = TransitGraph::from_parts(graph, rtree);
+        transit_graph.recompute_max_edge_speed();
+
+        let snapped = points
+            .iter()
+            .map(|&geometry| SnappedPoint::init(geometry, &transit_graph).unwrap())
+            .collect();
+
+        (transit_graph, snapped)
+    }
+
+    #[test]
+    fn test_landmark_cache_matches_exact_dijkstra() {
+        let (graph, points) = chain_graph();
+        // Anchor the cache at `b`, distinct from every query point below, so the
+        // comparison actually routes through `nearest_anchor`'s local search and
+        // the anchor's tree instead of hitting the `start == anchor` shortcut.
+        let cache = graph.build_cache(&points[1..2], 0, None);
+
+        let tolerance = 1e-6;
+        for (source_index, source) in points.iter().enumerate() {
+            if source_index == 1 {
+                continue;
+            }
+
+            let exact = time_dependent_dijkstra(
+                &graph,
+                *source.index(),
+                None,
+                0,
+                None,
+                &QueryOptions::default(),
+            );
+
+            for (target_index, target) in points.iter().enumerate() {
+                let exact_weight = exact.scores.get(target.index());
+                let cached_weight = cache.distance(&graph, source, target, None);
+
+                match (exact_weight, cached_weight) {
+                    (Some(&exact_weight), Some(cached_weight)) => {
+                        // The cache answers via the anchor `b`: 10s per hop from
+                        // the source to `b`, then from `b` to the target.
+                        let via_anchor =
+                            10.0 * (source_index.abs_diff(1) + target_index.abs_diff(1)) as f64;
+                        assert!((cached_weight - via_anchor).abs() < tolerance);
+                        // That detour is the shortest path exactly when `b` lies
+                        // between source and target on the chain.
+                        if source_index.min(target_index) <= 1 && 1 <= source_index.max(target_index) {
+                            assert!((exact_weight - cached_weight).abs() < tolerance);
+                        } else {
+                            assert!(cached_weight > exact_weight);
+                        }
+                    }
+                    (None, None) => {}
+                    (exact_weight, cached_weight) => {
+                        panic!(
+                            "reachability mismatch: exact={exact_weight:?}, cached={cached_weight:?}"
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
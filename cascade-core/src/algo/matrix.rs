@@ -0,0 +1,161 @@
+//! Parallel one-to-many travel-time matrices, and isochrones built from them.
+//!
+//! [`single_source_shortest_path_weight`](crate::algo::single_source_shortest_path_weight)
+//! answers one origin at a time; accessibility workloads need the same query
+//! run from hundreds of origins. [`travel_time_matrix`] runs it once per
+//! source on a dedicated `rayon` thread pool. The graph is only ever read
+//! during a query, so it can be shared by `&` across threads with no
+//! locking. [`connection_scan_matrix`] answers the same kind of query from a
+//! precomputed [`ConnectionIndex`] instead, for callers already reusing one
+//! across many searches. [`isochrone`] turns one such query's durations into
+//! a catchment polygon.
+
+use geo::prelude::*;
+use geo::MultiPoint;
+use geojson::{Feature, Geometry};
+use hashbrown::HashMap;
+use petgraph::graph::NodeIndex;
+use rayon::prelude::*;
+use rayon::ThreadPoolBuilder;
+use serde_json::{json, map::Map};
+
+use crate::algo::connection_scan::ConnectionIndex;
+use crate::algo::landmarks::LandmarkCache;
+use crate::algo::path_wrappers::single_source_shortest_path_weight;
+use crate::graph::{GraphNode, QueryOptions, TransitGraph};
+use crate::prelude::SnappedPoint;
+use crate::realtime::RealtimeOverlay;
+use crate::Error;
+
+/// Computes shortest-path weights from every point in `sources` to all
+/// reachable nodes, running one [`single_source_shortest_path_weight`] query
+/// per source in parallel.
+///
+/// `num_threads` is forwarded to [`rayon::ThreadPoolBuilder::num_threads`];
+/// pass `0` to let `rayon` pick the default (number of logical CPUs).
+///
+/// When `cache` is `Some`, each source is instead answered via
+/// [`LandmarkCache::scores_from`] — a bounded local search out to the
+/// nearest precomputed anchor tree instead of a fresh global search. This is
+/// only an approximation, and the cache's own `dep_time` is used rather than
+/// `start_time`, so `cache` should only be passed when it was built for the
+/// same `start_time` this call uses.
+///
+/// Returns one `HashMap<NodeIndex, f64>` per source, in the same order as
+/// `sources`. A node that is unreachable from a given source is simply
+/// absent from that source's map rather than recorded as `f64::INFINITY`.
+///
+/// # Errors
+/// Returns [`Error::ThreadPoolError`] if the thread pool fails to build.
+pub fn travel_time_matrix(
+    graph: &TransitGraph,
+    sources: &[SnappedPoint],
+    start_time: u32,
+    num_threads: usize,
+    overlay: Option<&RealtimeOverlay>,
+    cache: Option<&LandmarkCache>,
+) -> Result<Vec<HashMap<NodeIndex, f64>>, Error> {
+    let pool = ThreadPoolBuilder::new()
+        .num_threads(num_threads)
+        .build()
+        .map_err(|e| Error::ThreadPoolError(e.to_string()))?;
+
+    Ok(pool.install(|| {
+        sources
+            .par_iter()
+            .map(|source| match cache {
+                Some(cache) => cache.scores_from(graph, source, overlay),
+                None => single_source_shortest_path_weight(graph, source, start_time, overlay),
+            })
+            .collect()
+    }))
+}
+
+/// Computes earliest-arrival travel times from every point in `sources` to all
+/// reachable nodes using a precomputed [`ConnectionIndex`], running one
+/// [`ConnectionIndex::earliest_arrivals_from`] scan per source in parallel.
+///
+/// Prefer this over [`travel_time_matrix`] when `index` is already built and
+/// reused across many queries against the same graph snapshot, the way
+/// [`crate::algo::connection_scan_itinerary`] is preferred over a fresh
+/// Dijkstra search for single-pair queries: the CSA scan only ever reads
+/// `index`'s sorted connection array, so it shares across threads with no
+/// locking, the same way `travel_time_matrix` shares `graph`.
+///
+/// `num_threads` is forwarded to [`rayon::ThreadPoolBuilder::num_threads`];
+/// pass `0` to let `rayon` pick the default (number of logical CPUs).
+///
+/// Returns one `HashMap<NodeIndex, f64>` per source, in the same order as
+/// `sources`, with the same unreachable-node-is-absent convention as
+/// [`travel_time_matrix`].
+///
+/// # Errors
+/// Returns [`Error::ThreadPoolError`] if the thread pool fails to build.
+pub fn connection_scan_matrix(
+    graph: &TransitGraph,
+    index: &ConnectionIndex,
+    sources: &[SnappedPoint],
+    start_time: u32,
+    num_threads: usize,
+    wheelchair: bool,
+    overlay: Option<&RealtimeOverlay>,
+) -> Result<Vec<HashMap<NodeIndex, f64>>, Error> {
+    let options = QueryOptions {
+        wheelchair,
+        ..QueryOptions::default()
+    };
+
+    let pool = ThreadPoolBuilder::new()
+        .num_threads(num_threads)
+        .build()
+        .map_err(|e| Error::ThreadPoolError(e.to_string()))?;
+
+    Ok(pool.install(|| {
+        sources
+            .par_iter()
+            .map(|source| {
+                let distance = *source.distance();
+                let mut scores =
+                    index.earliest_arrivals_from(graph, *source.index(), start_time, &options, overlay);
+                scores.values_mut().for_each(|duration| *duration += distance);
+                scores
+            })
+            .collect()
+    }))
+}
+
+/// Builds an isochrone — the catchment area reachable within `cutoff_seconds`
+/// — from one source's [`single_source_shortest_path_weight`] durations.
+///
+/// Collects every walk node whose duration is within `cutoff_seconds`, takes
+/// the convex hull of their geometries, and returns it as a single GeoJSON
+/// `Feature`, matching the feature/property shape used by
+/// [`crate::algo::itinerary::Itinerary::to_geojson`].
+#[must_use]
+pub fn isochrone(
+    graph: &TransitGraph,
+    scores: &HashMap<NodeIndex, f64>,
+    cutoff_seconds: f64,
+) -> geojson::GeoJson {
+    let reachable_points: Vec<_> = scores
+        .iter()
+        .filter(|&(_, &duration)| duration <= cutoff_seconds)
+        .filter_map(|(&node, _)| match graph.into_inner_graph().node_weight(node) {
+            Some(GraphNode::Walk(walk_node)) => Some(walk_node.geometry),
+            _ => None,
+        })
+        .collect();
+
+    let hull = MultiPoint::new(reachable_points).convex_hull();
+
+    let mut properties = Map::new();
+    properties.insert("cutoff_seconds".to_string(), json!(cutoff_seconds));
+
+    geojson::GeoJson::Feature(Feature {
+        geometry: Some(Geometry::new((&hull).into())),
+        properties: Some(properties),
+        id: None,
+        bbox: None,
+        foreign_members: None,
+    })
+}
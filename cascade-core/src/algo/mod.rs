@@ -1,9 +1,29 @@
+pub mod connection_scan;
+pub mod dary_heap;
 pub mod dijkstra;
 pub mod itinerary;
+pub mod landmarks;
+pub mod matrix;
+pub mod pareto;
 pub mod path_wrappers;
+pub mod steiner;
+pub mod tour;
 
-pub use itinerary::detailed_itinerary;
-pub use path_wrappers::{shortest_path, shortest_path_weight, single_source_shortest_path_weight};
+pub use connection_scan::{connection_scan_itinerary, ConnectionIndex};
+pub use itinerary::{
+    all_optimal_itineraries, detailed_itinerary, detailed_itinerary_astar, k_shortest_itineraries,
+};
+pub use landmarks::LandmarkCache;
+pub use matrix::{connection_scan_matrix, isochrone, travel_time_matrix};
+pub use pareto::{
+    pareto_front, select_preferred, single_source_pareto_front, Label, RoutingPreference,
+};
+pub use path_wrappers::{
+    shortest_path, shortest_path_itinerary, shortest_path_itinerary_beam, shortest_path_weight,
+    single_source_shortest_path_weight, single_source_shortest_path_weight_multi,
+};
+pub use steiner::steiner_network;
+pub use tour::optimal_tour;
 
 use std::cmp::Ordering;
 
@@ -0,0 +1,380 @@
+//! Multi-criteria (time + transfers + walking) Pareto-optimal routing.
+//!
+//! [`crate::algo::dijkstra::time_dependent_dijkstra`] optimizes arrival time
+//! only, so a journey that is marginally faster but requires several extra
+//! transfers always wins. This module instead keeps, per node, a bag of
+//! non-dominated [`Label`]s tracking `(arrival_time, num_transfers,
+//! walk_seconds)`, expanding the frontier with a priority queue ordered by
+//! arrival time. [`RoutingPreference`] then collapses a Pareto front down to
+//! a single journey by lexicographic priority.
+
+use std::collections::BinaryHeap;
+
+use hashbrown::HashMap;
+use petgraph::graph::NodeIndex;
+use petgraph::visit::EdgeRef;
+
+use crate::algo::MinScored;
+use crate::graph::{GraphEdge, TransitGraph};
+use crate::interner::RouteId;
+use crate::prelude::SnappedPoint;
+
+/// A single non-dominated journey tracked by the Pareto search: how early it
+/// arrives, how many transfers it took, and how much of it was spent
+/// walking.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Label {
+    pub arrival_time: u32,
+    pub num_transfers: u32,
+    pub walk_seconds: f64,
+    current_route: Option<RouteId>,
+}
+
+impl Label {
+    /// A label dominates another only if it is `<=` on all three criteria.
+    fn dominates(&self, other: &Self) -> bool {
+        self.arrival_time <= other.arrival_time
+            && self.num_transfers <= other.num_transfers
+            && self.walk_seconds <= other.walk_seconds
+    }
+}
+
+/// Lexicographic priority used to collapse a Pareto front down to a single
+/// journey.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoutingPreference {
+    Fastest,
+    FewestTransfers,
+    LeastWalking,
+}
+
+/// Picks the single best label from a Pareto front according to
+/// `preference`, breaking ties with the remaining two criteria in the order
+/// they're listed on [`RoutingPreference`].
+#[must_use]
+pub fn select_preferred(front: &[Label], preference: RoutingPreference) -> Option<&Label> {
+    front
+        .iter()
+        .min_by(|a, b| compare_by_preference(a, b, preference))
+}
+
+fn compare_by_preference(
+    a: &Label,
+    b: &Label,
+    preference: RoutingPreference,
+) -> std::cmp::Ordering {
+    use std::cmp::Ordering;
+
+    let arrival = || a.arrival_time.cmp(&b.arrival_time);
+    let transfers = || a.num_transfers.cmp(&b.num_transfers);
+    let walking = || {
+        a.walk_seconds
+            .partial_cmp(&b.walk_seconds)
+            .unwrap_or(Ordering::Equal)
+    };
+
+    match preference {
+        RoutingPreference::Fastest => arrival().then_with(transfers).then_with(walking),
+        RoutingPreference::FewestTransfers => transfers().then_with(arrival).then_with(walking),
+        RoutingPreference::LeastWalking => walking().then_with(arrival).then_with(transfers),
+    }
+}
+
+/// Runs the label-setting multicriteria search from `start`, returning the
+/// Pareto front of non-dominated labels reached at every node.
+///
+/// Labels whose `num_transfers` would exceed `max_transfers` are discarded,
+/// guarding against label explosion on dense transit networks.
+#[must_use]
+pub(crate) fn pareto_shortest_path(
+    graph: &TransitGraph,
+    start: NodeIndex,
+    start_time: u32,
+    max_transfers: u32,
+) -> HashMap<NodeIndex, Vec<Label>> {
+    let mut bags: HashMap<NodeIndex, Vec<Label>> = HashMap::new();
+    let mut arena: Vec<(NodeIndex, Label)> = Vec::new();
+    let mut visit_next = BinaryHeap::new();
+
+    let start_label = Label {
+        arrival_time: start_time,
+        num_transfers: 0,
+        walk_seconds: 0.0,
+        current_route: None,
+    };
+
+    bags.entry(start).or_default().push(start_label);
+    arena.push((start, start_label));
+    visit_next.push(MinScored(f64::from(start_time), 0_usize));
+
+    while let Some(MinScored(_, label_idx)) = visit_next.pop() {
+        let (node, label) = arena[label_idx];
+
+        // The label may have since been dominated out of its node's bag;
+        // skip expanding it if so.
+        if !bags
+            .get(&node)
+            .is_some_and(|bag| bag.iter().any(|candidate| *candidate == label))
+        {
+            continue;
+        }
+
+        for edge in graph.edges(node) {
+            let next = edge.target();
+
+            let Some(new_label) = relax(edge.weight(), &label, max_transfers) else {
+                continue;
+            };
+
+            let bag = bags.entry(next).or_default();
+            if bag.iter().any(|existing| existing.dominates(&new_label)) {
+                continue;
+            }
+            bag.retain(|existing| !new_label.dominates(existing));
+            bag.push(new_label);
+
+            let idx = arena.len();
+            arena.push((next, new_label));
+            visit_next.push(MinScored(f64::from(new_label.arrival_time), idx));
+        }
+    }
+
+    bags
+}
+
+/// Relaxes a single edge from `label`, returning the resulting label unless
+/// the edge offers no onward trip or would exceed `max_transfers`.
+fn relax(edge: &GraphEdge, label: &Label, max_transfers: u32) -> Option<Label> {
+    match edge {
+        GraphEdge::Transit(transit_edge) => {
+            let trips = &transit_edge.edge_trips;
+            let index = match trips
+                .binary_search_by(|trip| trip.departure_time.cmp(&label.arrival_time))
+            {
+                Ok(index) | Err(index) if index < trips.len() => index,
+                _ => return None,
+            };
+            let trip = trips[index];
+
+            let num_transfers =
+                label.num_transfers + u32::from(label.current_route != Some(trip.route_id));
+            if num_transfers > max_transfers {
+                return None;
+            }
+
+            Some(Label {
+                arrival_time: trip.arrival_time,
+                num_transfers,
+                walk_seconds: label.walk_seconds,
+                current_route: Some(trip.route_id),
+            })
+        }
+        GraphEdge::Transfer(walk_edge) | GraphEdge::Walk(walk_edge) => {
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let arrival_time = label.arrival_time + walk_edge.edge_weight as u32;
+
+            Some(Label {
+                arrival_time,
+                num_transfers: label.num_transfers,
+                walk_seconds: label.walk_seconds + walk_edge.edge_weight,
+                current_route: label.current_route,
+            })
+        }
+    }
+}
+
+/// Runs [`pareto_shortest_path`] from a [`SnappedPoint`], adding the
+/// point's snap distance to every returned label's `arrival_time` and
+/// `walk_seconds` so the initial walk to the graph is accounted for.
+#[must_use]
+pub fn single_source_pareto_front(
+    graph: &TransitGraph,
+    start: &SnappedPoint,
+    start_time: u32,
+    max_transfers: u32,
+) -> HashMap<NodeIndex, Vec<Label>> {
+    let source_index = start.index();
+    let distance = *start.distance();
+
+    let mut bags = pareto_shortest_path(graph, *source_index, start_time, max_transfers);
+    for labels in bags.values_mut() {
+        for label in labels {
+            apply_snap_distance(label, distance);
+        }
+    }
+    bags
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn apply_snap_distance(label: &mut Label, distance: f64) {
+    label.arrival_time += distance as u32;
+    label.walk_seconds += distance;
+}
+
+/// Returns the Pareto front of non-dominated journeys from `start` to
+/// `target`, or an empty front if `target` is unreachable.
+#[must_use]
+pub fn pareto_front(
+    graph: &TransitGraph,
+    start: &SnappedPoint,
+    target: &SnappedPoint,
+    start_time: u32,
+    max_transfers: u32,
+) -> Vec<Label> {
+    let bags = single_source_pareto_front(graph, start, start_time, max_transfers);
+    bags.get(target.index()).cloned().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{TransitEdge, Trip, WalkEdge};
+    use crate::interner::{StopId, TripId};
+
+    fn label(arrival_time: u32, num_transfers: u32, walk_seconds: f64) -> Label {
+        Label {
+            arrival_time,
+            num_transfers,
+            walk_seconds,
+            current_route: None,
+        }
+    }
+
+    #[test]
+    fn test_dominates() {
+        let faster_fewer_transfers = label(100, 0, 50.0);
+        let slower_more_transfers = label(200, 1, 50.0);
+
+        assert!(faster_fewer_transfers.dominates(&slower_more_transfers));
+        assert!(!slower_more_transfers.dominates(&faster_fewer_transfers));
+    }
+
+    #[test]
+    fn test_non_dominated_labels_do_not_dominate_each_other() {
+        let fast_but_walky = label(100, 0, 500.0);
+        let slow_but_direct = label(200, 0, 50.0);
+
+        assert!(!fast_but_walky.dominates(&slow_but_direct));
+        assert!(!slow_but_direct.dominates(&fast_but_walky));
+    }
+
+    #[test]
+    fn test_relax_counts_transfers_only_on_route_change() {
+        let boarding = label(1000, 0, 0.0);
+
+        let trip_a = Trip {
+            route_id: RouteId(0),
+            departure_time: 1000,
+            arrival_time: 1100,
+            wheelchair_accessible: true,
+            trip_headsign: None,
+            trip_id: TripId(0),
+        };
+        let edge_a = GraphEdge::Transit(TransitEdge {
+            edge_trips: vec![trip_a],
+            source_stop: StopId(0),
+            target_stop: StopId(1),
+            geometry: None,
+            boarding_wheelchair_accessible: true,
+        });
+
+        // Boarding the first route from a label with no current route is not
+        // a transfer.
+        let after_a = relax(&edge_a, &boarding, 10).expect("trip should be found");
+        assert_eq!(after_a.num_transfers, 0);
+        assert_eq!(after_a.current_route, Some(RouteId(0)));
+
+        // Continuing on the same route doesn't count as a transfer...
+        let trip_a_again = Trip {
+            route_id: RouteId(0),
+            departure_time: 1100,
+            arrival_time: 1200,
+            wheelchair_accessible: true,
+            trip_headsign: None,
+            trip_id: TripId(1),
+        };
+        let edge_a_again = GraphEdge::Transit(TransitEdge {
+            edge_trips: vec![trip_a_again],
+            source_stop: StopId(1),
+            target_stop: StopId(2),
+            geometry: None,
+            boarding_wheelchair_accessible: true,
+        });
+        let same_route = relax(&edge_a_again, &after_a, 10).expect("trip should be found");
+        assert_eq!(same_route.num_transfers, 0);
+
+        // ...but boarding a different route does.
+        let trip_b = Trip {
+            route_id: RouteId(1),
+            departure_time: 1200,
+            arrival_time: 1300,
+            wheelchair_accessible: true,
+            trip_headsign: None,
+            trip_id: TripId(2),
+        };
+        let edge_b = GraphEdge::Transit(TransitEdge {
+            edge_trips: vec![trip_b],
+            source_stop: StopId(2),
+            target_stop: StopId(3),
+            geometry: None,
+            boarding_wheelchair_accessible: true,
+        });
+        let new_route = relax(&edge_b, &same_route, 10).expect("trip should be found");
+        assert_eq!(new_route.num_transfers, 1);
+
+        // A walk/transfer edge never changes the transfer count or route.
+        let walk_edge = WalkEdge {
+            edge_weight: 30.0,
+            geometry: None,
+        };
+        let walked = relax(&GraphEdge::Walk(walk_edge), &new_route, 10).expect("walk always succeeds");
+        assert_eq!(walked.num_transfers, 1);
+        assert_eq!(walked.current_route, Some(RouteId(1)));
+    }
+
+    #[test]
+    fn test_relax_respects_max_transfers() {
+        let boarding = Label {
+            arrival_time: 1000,
+            num_transfers: 2,
+            walk_seconds: 0.0,
+            current_route: Some(RouteId(0)),
+        };
+        let trip = Trip {
+            route_id: RouteId(1),
+            departure_time: 1000,
+            arrival_time: 1100,
+            wheelchair_accessible: true,
+            trip_headsign: None,
+            trip_id: TripId(0),
+        };
+        let edge = GraphEdge::Transit(TransitEdge {
+            edge_trips: vec![trip],
+            source_stop: StopId(0),
+            target_stop: StopId(1),
+            geometry: None,
+            boarding_wheelchair_accessible: true,
+        });
+
+        assert!(relax(&edge, &boarding, 2).is_none());
+        assert!(relax(&edge, &boarding, 3).is_some());
+    }
+
+    #[test]
+    fn test_select_preferred() {
+        let front = vec![label(100, 2, 500.0), label(200, 0, 50.0), label(150, 1, 100.0)];
+
+        assert_eq!(
+            select_preferred(&front, RoutingPreference::Fastest),
+            Some(&front[0])
+        );
+        assert_eq!(
+            select_preferred(&front, RoutingPreference::FewestTransfers),
+            Some(&front[1])
+        );
+        assert_eq!(
+            select_preferred(&front, RoutingPreference::LeastWalking),
+            Some(&front[1])
+        );
+    }
+}
@@ -1,9 +1,14 @@
 use hashbrown::HashMap;
 use petgraph::graph::NodeIndex;
 
-use crate::algo::dijkstra::time_dependent_dijkstra;
-use crate::graph::TransitGraph;
+use crate::algo::dijkstra::{
+    time_dependent_a_star, time_dependent_beam_search, time_dependent_dijkstra,
+    time_dependent_dijkstra_multi_start,
+};
+use crate::algo::itinerary::Itinerary;
+use crate::graph::{QueryOptions, TransitGraph};
 use crate::prelude::SnappedPoint;
+use crate::realtime::RealtimeOverlay;
 use crate::Error;
 
 #[must_use]
@@ -11,26 +16,94 @@ pub fn single_source_shortest_path_weight(
     graph: &TransitGraph,
     start: &SnappedPoint,
     start_time: u32,
+    overlay: Option<&RealtimeOverlay>,
 ) -> HashMap<NodeIndex, f64> {
     let source_index = start.index();
     let distance = *start.distance();
-    let mut result = time_dependent_dijkstra(graph, *source_index, None, start_time);
+    let mut result = time_dependent_dijkstra(
+        graph,
+        *source_index,
+        None,
+        start_time,
+        overlay,
+        &QueryOptions::default(),
+    )
+    .scores;
     // add distance to all values in the result
     result.iter_mut().for_each(|(_, v)| *v += distance);
     result
 }
 
+/// Finds the shortest path weight from every node reachable on foot from a
+/// multi-stop access catchment, like [`single_source_shortest_path_weight`]
+/// but seeded from several `starts` (e.g. every stop within walking distance
+/// of a query point, from [`SnappedPoint::init_within`]) instead of one
+/// nearest-node snap.
+///
+/// Each start contributes its own snap distance as the walk-access time
+/// feeding the search, so a start that's slightly farther from the query
+/// point doesn't unfairly compete on equal footing with the nearest one.
+#[must_use]
+pub fn single_source_shortest_path_weight_multi(
+    graph: &TransitGraph,
+    starts: &[SnappedPoint],
+    start_time: u32,
+    overlay: Option<&RealtimeOverlay>,
+) -> HashMap<NodeIndex, f64> {
+    let seeds: Vec<(NodeIndex, f64)> = starts
+        .iter()
+        .map(|start| (*start.index(), *start.distance()))
+        .collect();
+
+    time_dependent_dijkstra_multi_start(
+        graph,
+        &seeds,
+        None,
+        start_time,
+        overlay,
+        &QueryOptions::default(),
+    )
+    .scores
+}
+
+/// Finds the shortest path weight from `start` to `target`.
+///
+/// `astar` picks the search strategy: `true` runs [`time_dependent_a_star`],
+/// which uses a geographic lower-bound heuristic to avoid expanding the
+/// whole reachable frontier and is the faster choice on city-scale graphs;
+/// `false` runs plain [`time_dependent_dijkstra`]. Both are exact — the
+/// heuristic is admissible and consistent, so `astar` never changes the
+/// result, only how much of the graph is explored to find it.
+///
+/// # Errors
+/// Returns [`Error::MissingValue`] if `target` is unreachable from `start`.
 pub fn shortest_path_weight(
     graph: &TransitGraph,
     start: &SnappedPoint,
     target: &SnappedPoint,
     start_time: u32,
+    overlay: Option<&RealtimeOverlay>,
+    astar: bool,
 ) -> Result<f64, Error> {
     let source_index = start.index();
     let target_index = target.index();
+    let options = QueryOptions::default();
 
     let distance = *start.distance();
-    let result = time_dependent_dijkstra(graph, *source_index, Some(*target_index), start_time);
+    let result = if astar {
+        time_dependent_a_star(graph, *source_index, *target_index, start_time, overlay, &options)
+            .scores
+    } else {
+        time_dependent_dijkstra(
+            graph,
+            *source_index,
+            Some(*target_index),
+            start_time,
+            overlay,
+            &options,
+        )
+        .scores
+    };
     // add distance to all values in the result
     let time = result.get(target_index).ok_or(Error::MissingValue(format!(
         "failed to extract time for node {target_index:?}"
@@ -38,3 +111,83 @@ pub fn shortest_path_weight(
 
     Ok(*time + distance)
 }
+
+/// Finds the shortest path from `start` to `target`, like [`shortest_path_weight`],
+/// but returns the full boarded [`Itinerary`] — trips, transfers, and geometry —
+/// rather than just the duration.
+///
+/// `options` is consulted both while searching (so the settled path never
+/// boards a trip or walk leg the rider can't use) and while reconstructing
+/// the itinerary from it, so the two stay consistent with each other.
+///
+/// # Errors
+/// Returns [`Error::MissingValue`] if `target` is unreachable from `start`.
+pub fn shortest_path_itinerary<'a>(
+    graph: &'a TransitGraph,
+    start: &SnappedPoint,
+    target: &SnappedPoint,
+    start_time: u32,
+    options: &QueryOptions,
+    overlay: Option<&RealtimeOverlay>,
+) -> Result<Itinerary<'a>, Error> {
+    let source_index = start.index();
+    let target_index = target.index();
+
+    let search = time_dependent_dijkstra(
+        graph,
+        *source_index,
+        Some(*target_index),
+        start_time,
+        overlay,
+        options,
+    );
+
+    search
+        .reconstruct_itinerary(graph, *target_index, options, overlay)
+        .ok_or_else(|| {
+            Error::MissingValue(format!("failed to extract itinerary for node {target_index:?}"))
+        })
+}
+
+/// Finds the shortest path from `start` to `target`, like
+/// [`shortest_path_itinerary`], but runs [`time_dependent_beam_search`]
+/// instead of the exact Dijkstra search, capping the frontier to
+/// `beam_width` `(node, time)` states per level when it's `Some` — trading
+/// optimality for a bounded memory/latency footprint on very large graphs.
+/// `beam_width: None` is equivalent to [`shortest_path_itinerary`]'s exact
+/// search. The returned itinerary is always a valid connected path; when the
+/// beam prunes away the true optimum it's just not guaranteed shortest.
+///
+/// # Errors
+/// Returns [`Error::MissingValue`] if `target` isn't reachable from `start`
+/// within the beam.
+pub fn shortest_path_itinerary_beam<'a>(
+    graph: &'a TransitGraph,
+    start: &SnappedPoint,
+    target: &SnappedPoint,
+    start_time: u32,
+    options: &QueryOptions,
+    overlay: Option<&RealtimeOverlay>,
+    beam_width: Option<usize>,
+) -> Result<Itinerary<'a>, Error> {
+    let source_index = start.index();
+    let target_index = target.index();
+
+    let search = time_dependent_beam_search(
+        graph,
+        *source_index,
+        *target_index,
+        start_time,
+        overlay,
+        options,
+        beam_width,
+    );
+
+    search
+        .reconstruct_itinerary(graph, *target_index, options, overlay)
+        .ok_or_else(|| {
+            Error::MissingValue(format!(
+                "failed to extract itinerary for node {target_index:?} within beam width {beam_width:?}"
+            ))
+        })
+}
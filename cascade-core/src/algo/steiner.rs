@@ -0,0 +1,151 @@
+//! Steiner-tree approximation connecting a set of terminal stops with a
+//! near-minimal time-dependent subnetwork.
+//!
+//! [`steiner_network`] implements the classic 2-approximation over the
+//! metric closure: run a full single-source search from every terminal,
+//! build the complete distance graph among terminals from those results,
+//! take its minimum spanning tree, then expand each MST edge back into the
+//! real path it came from and union the edges.
+
+use geo::{LineString, MultiLineString};
+use geojson::{Feature, Geometry};
+use hashbrown::HashSet;
+use petgraph::graph::EdgeIndex;
+use serde_json::{json, map::Map};
+
+use crate::algo::dijkstra::time_dependent_dijkstra;
+use crate::graph::{QueryOptions, TransitGraph};
+use crate::prelude::SnappedPoint;
+use crate::realtime::RealtimeOverlay;
+
+/// Computes a near-minimal connected subnetwork spanning `terminals`, using
+/// the classic 2-approximation for the Steiner tree problem over the metric
+/// closure:
+///
+/// 1. Run [`time_dependent_dijkstra`] from every terminal to build the
+///    complete (and possibly asymmetric) distance graph among terminals.
+/// 2. Take the minimum spanning tree of that complete graph, using the
+///    cheaper direction of each terminal pair as its undirected weight.
+/// 3. Expand each MST edge back into the edges of the real shortest path it
+///    came from, via `PathSearch::path_edges`.
+/// 4. Union the expanded edges, dropping duplicates, and combine their
+///    geometries into a single `MultiLineString`.
+///
+/// Returns a GeoJSON `Feature` wrapping that combined geometry, with a
+/// `total_weight` property holding the MST's total weight in seconds —
+/// matching the feature/property shape used elsewhere in this module (see
+/// [`crate::algo::isochrone`]).
+#[must_use]
+pub fn steiner_network(
+    graph: &TransitGraph,
+    terminals: &[SnappedPoint],
+    start_time: u32,
+    overlay: Option<&RealtimeOverlay>,
+) -> geojson::GeoJson {
+    let options = QueryOptions::default();
+    let n = terminals.len();
+
+    let searches: Vec<_> = terminals
+        .iter()
+        .map(|terminal| {
+            time_dependent_dijkstra(graph, *terminal.index(), None, start_time, overlay, &options)
+        })
+        .collect();
+
+    let mut dist = vec![vec![f64::INFINITY; n]; n];
+    for i in 0..n {
+        for j in 0..n {
+            if i == j {
+                continue;
+            }
+            if let Some(&weight) = searches[i].scores.get(terminals[j].index()) {
+                dist[i][j] = weight;
+            }
+        }
+    }
+
+    let mut used_edges: HashSet<EdgeIndex> = HashSet::new();
+    let mut total_weight = 0.0;
+
+    for (a, b) in terminal_mst(&dist) {
+        // Expand using whichever direction of the pair is cheaper, matching
+        // the direction the MST weight itself was drawn from.
+        let (source, target, weight) = if dist[a][b] <= dist[b][a] {
+            (a, b, dist[a][b])
+        } else {
+            (b, a, dist[b][a])
+        };
+
+        total_weight += weight;
+
+        if let Some(edges) = searches[source].path_edges(*terminals[target].index()) {
+            used_edges.extend(edges);
+        }
+    }
+
+    let geometry: Vec<LineString> = used_edges
+        .iter()
+        .filter_map(|&edge_index| graph.into_inner_graph().edge_weight(edge_index))
+        .filter_map(|edge| edge.geometry().cloned())
+        .collect();
+
+    let mut properties = Map::new();
+    properties.insert("total_weight".to_string(), json!(total_weight));
+
+    geojson::GeoJson::Feature(Feature {
+        geometry: Some(Geometry::new((&MultiLineString::new(geometry)).into())),
+        properties: Some(properties),
+        id: None,
+        bbox: None,
+        foreign_members: None,
+    })
+}
+
+/// Prim's algorithm over the complete, symmetrized distance graph among
+/// terminals: `dist[i][j]` and `dist[j][i]` may differ (the underlying graph
+/// is time-dependent and directed), so the edge weight between `i` and `j`
+/// is their cheaper direction. Returns the selected `(i, j)` pairs.
+fn terminal_mst(dist: &[Vec<f64>]) -> Vec<(usize, usize)> {
+    let n = dist.len();
+    if n < 2 {
+        return Vec::new();
+    }
+
+    let pair_weight = |i: usize, j: usize| dist[i][j].min(dist[j][i]);
+
+    let mut in_tree = vec![false; n];
+    let mut best_weight = vec![f64::INFINITY; n];
+    let mut best_from = vec![usize::MAX; n];
+
+    in_tree[0] = true;
+    for j in 1..n {
+        best_weight[j] = pair_weight(0, j);
+        best_from[j] = 0;
+    }
+
+    let mut edges = Vec::with_capacity(n - 1);
+
+    for _ in 1..n {
+        let Some(next) = (0..n)
+            .filter(|&j| !in_tree[j] && best_weight[j].is_finite())
+            .min_by(|&a, &b| best_weight[a].partial_cmp(&best_weight[b]).unwrap())
+        else {
+            break;
+        };
+
+        in_tree[next] = true;
+        edges.push((best_from[next], next));
+
+        for j in 0..n {
+            if !in_tree[j] {
+                let weight = pair_weight(next, j);
+                if weight < best_weight[j] {
+                    best_weight[j] = weight;
+                    best_from[j] = next;
+                }
+            }
+        }
+    }
+
+    edges
+}
@@ -0,0 +1,287 @@
+//! Optimal multi-waypoint itinerary ordering — the time-dependent transit
+//! analogue of the travelling salesman problem.
+//!
+//! [`optimal_tour`] finds the time-minimizing order to visit a set of
+//! intermediate stops, then concatenates the per-leg itineraries into one
+//! continuous [`Itinerary`]. This serves delivery/patrol-style planning
+//! where the visiting order is free, unlike [`crate::algo::shortest_path_itinerary`]
+//! which assumes a fixed origin/destination pair.
+
+use hashbrown::HashMap;
+use petgraph::graph::NodeIndex;
+
+use crate::algo::dijkstra::time_dependent_dijkstra;
+use crate::algo::itinerary::Itinerary;
+use crate::graph::{QueryOptions, TransitGraph};
+use crate::prelude::SnappedPoint;
+use crate::realtime::RealtimeOverlay;
+use crate::Error;
+
+/// Above this many waypoints, [`optimal_tour`] falls back to a
+/// nearest-neighbor construction improved by 2-opt instead of the exact
+/// Held-Karp DP, whose `O(2^n * n^2)` cost becomes impractical.
+const HELD_KARP_LIMIT: usize = 12;
+
+/// Finds the time-minimizing order to visit every stop in `points`, starting
+/// from `points[0]`, and returns that order (as positions into `points`)
+/// alongside the concatenated [`Itinerary`] that visits them in sequence.
+///
+/// `return_to_start` additionally requires (and costs in) a final leg back
+/// to `points[0]` after every other stop has been visited.
+///
+/// Up to [`HELD_KARP_LIMIT`] waypoints are ordered exactly via Held-Karp
+/// bitmask DP; beyond that a nearest-neighbor tour improved with 2-opt is
+/// used instead, trading optimality for tractable runtime.
+///
+/// The ordering is solved against a cost matrix where each row is a single
+/// [`time_dependent_dijkstra`] search departing its waypoint at `start_time`
+/// — exact for the first leg, and an approximation for every leg after it,
+/// since the true departure time from a later waypoint depends on the
+/// cumulative travel time to reach it. The itinerary reconstruction pass
+/// does not share this approximation: each leg's boarding choices are
+/// re-evaluated with the clock advanced to the actual cumulative arrival
+/// time at the previous stop.
+///
+/// # Errors
+/// Returns [`Error::MissingValue`] if `points` is empty, or if any waypoint
+/// in the solved order turns out to be unreachable from its predecessor
+/// when the itinerary is reconstructed.
+pub fn optimal_tour<'a>(
+    graph: &'a TransitGraph,
+    points: &[SnappedPoint],
+    start_time: u32,
+    return_to_start: bool,
+    overlay: Option<&RealtimeOverlay>,
+) -> Result<(Vec<usize>, Itinerary<'a>), Error> {
+    let n = points.len();
+    if n == 0 {
+        return Err(Error::MissingValue(
+            "optimal_tour requires at least one waypoint".to_string(),
+        ));
+    }
+
+    let options = QueryOptions::default();
+    let matrix = build_cost_matrix(graph, points, start_time, overlay, &options);
+
+    let order = if n <= HELD_KARP_LIMIT {
+        held_karp_order(&matrix, return_to_start)
+    } else {
+        two_opt(&matrix, nearest_neighbor_order(&matrix), return_to_start)
+    };
+
+    let itinerary = reconstruct_itinerary(
+        graph, points, &order, start_time, return_to_start, overlay, &options,
+    )?;
+
+    Ok((order, itinerary))
+}
+
+/// Builds the asymmetric `n x n` travel-time matrix among `points`, running
+/// one [`time_dependent_dijkstra`] search per waypoint, each departing at
+/// `start_time`. `matrix[i][j]` is the leg time from `points[i]` to
+/// `points[j]`, including both endpoints' snap-walk distances; `f64::INFINITY`
+/// if `points[j]` isn't reachable from `points[i]`.
+fn build_cost_matrix(
+    graph: &TransitGraph,
+    points: &[SnappedPoint],
+    start_time: u32,
+    overlay: Option<&RealtimeOverlay>,
+    options: &QueryOptions,
+) -> Vec<Vec<f64>> {
+    let position_of: HashMap<NodeIndex, usize> = points
+        .iter()
+        .enumerate()
+        .map(|(position, point)| (*point.index(), position))
+        .collect();
+
+    points
+        .iter()
+        .map(|origin| {
+            let mut row = vec![f64::INFINITY; points.len()];
+            let search =
+                time_dependent_dijkstra(graph, *origin.index(), None, start_time, overlay, options);
+
+            for (node, weight) in search.scores {
+                if let Some(&position) = position_of.get(&node) {
+                    row[position] = weight + *origin.distance() + *points[position].distance();
+                }
+            }
+            row
+        })
+        .collect()
+}
+
+/// Exact solution to the (possibly asymmetric) TSP over `matrix` via
+/// Held-Karp bitmask DP: `dp[mask][j]` is the minimum time to start at
+/// waypoint `0`, visit exactly the stops in `mask`, and end at `j`. Returns
+/// the visiting order as positions into `matrix`, including the return leg
+/// to `0` in the cost comparison when `return_to_start` is set.
+fn held_karp_order(matrix: &[Vec<f64>], return_to_start: bool) -> Vec<usize> {
+    let n = matrix.len();
+    let full_mask = (1_usize << n) - 1;
+
+    let mut dp = vec![vec![f64::INFINITY; n]; 1 << n];
+    let mut parent = vec![vec![usize::MAX; n]; 1 << n];
+    dp[1][0] = 0.0;
+
+    for mask in 1..=full_mask {
+        if mask & 1 == 0 {
+            continue;
+        }
+        for j in 0..n {
+            if mask & (1 << j) == 0 || dp[mask][j].is_infinite() {
+                continue;
+            }
+            for k in 0..n {
+                if mask & (1 << k) != 0 {
+                    continue;
+                }
+                let next_mask = mask | (1 << k);
+                let candidate = dp[mask][j] + matrix[j][k];
+                if candidate < dp[next_mask][k] {
+                    dp[next_mask][k] = candidate;
+                    parent[next_mask][k] = j;
+                }
+            }
+        }
+    }
+
+    let mut best_end = 0;
+    let mut best_weight = f64::INFINITY;
+    for j in 0..n {
+        let mut weight = dp[full_mask][j];
+        if weight.is_infinite() {
+            continue;
+        }
+        if return_to_start {
+            weight += matrix[j][0];
+        }
+        if weight < best_weight {
+            best_weight = weight;
+            best_end = j;
+        }
+    }
+
+    let mut order = Vec::with_capacity(n);
+    let mut mask = full_mask;
+    let mut node = best_end;
+    loop {
+        order.push(node);
+        let prev = parent[mask][node];
+        if prev == usize::MAX {
+            break;
+        }
+        mask ^= 1 << node;
+        node = prev;
+    }
+    order.reverse();
+
+    order
+}
+
+/// Greedy tour seed for the 2-opt fallback: starting at waypoint `0`,
+/// repeatedly hop to the nearest unvisited waypoint.
+fn nearest_neighbor_order(matrix: &[Vec<f64>]) -> Vec<usize> {
+    let n = matrix.len();
+    let mut visited = vec![false; n];
+    let mut order = Vec::with_capacity(n);
+
+    let mut current = 0;
+    visited[0] = true;
+    order.push(0);
+
+    for _ in 1..n {
+        let next = (0..n)
+            .filter(|&j| !visited[j])
+            .min_by(|&a, &b| matrix[current][a].partial_cmp(&matrix[current][b]).unwrap());
+
+        let Some(next) = next else { break };
+        visited[next] = true;
+        order.push(next);
+        current = next;
+    }
+
+    order
+}
+
+/// Total weight of visiting `order` in sequence, including the return leg to
+/// `order[0]` when `return_to_start` is set.
+fn tour_weight(matrix: &[Vec<f64>], order: &[usize], return_to_start: bool) -> f64 {
+    let mut total: f64 = order.windows(2).map(|pair| matrix[pair[0]][pair[1]]).sum();
+    if return_to_start {
+        if let (Some(&first), Some(&last)) = (order.first(), order.last()) {
+            total += matrix[last][first];
+        }
+    }
+    total
+}
+
+/// Improves `order` by repeatedly reversing segments (keeping the start,
+/// `order[0]`, fixed) whenever doing so lowers the total tour weight, until
+/// no reversal helps.
+fn two_opt(matrix: &[Vec<f64>], mut order: Vec<usize>, return_to_start: bool) -> Vec<usize> {
+    let n = order.len();
+    let mut best_weight = tour_weight(matrix, &order, return_to_start);
+
+    let mut improved = true;
+    while improved {
+        improved = false;
+        for i in 1..n.saturating_sub(1) {
+            for j in (i + 1)..n {
+                order[i..=j].reverse();
+                let weight = tour_weight(matrix, &order, return_to_start);
+                if weight < best_weight {
+                    best_weight = weight;
+                    improved = true;
+                } else {
+                    order[i..=j].reverse();
+                }
+            }
+        }
+    }
+
+    order
+}
+
+/// Walks `order`, concatenating the exact boarded [`Itinerary`] for each leg
+/// — re-evaluated from the actual cumulative arrival clock, not the
+/// approximate [`build_cost_matrix`] used to choose `order` — into one
+/// continuous itinerary.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn reconstruct_itinerary<'a>(
+    graph: &'a TransitGraph,
+    points: &[SnappedPoint],
+    order: &[usize],
+    start_time: u32,
+    return_to_start: bool,
+    overlay: Option<&RealtimeOverlay>,
+    options: &QueryOptions,
+) -> Result<Itinerary<'a>, Error> {
+    let mut itinerary = Itinerary::new();
+    let mut current_time = start_time;
+
+    let mut stops: Vec<usize> = order.to_vec();
+    if return_to_start {
+        if let Some(&first) = order.first() {
+            stops.push(first);
+        }
+    }
+
+    for pair in stops.windows(2) {
+        let leg = crate::algo::shortest_path_itinerary(
+            graph,
+            &points[pair[0]],
+            &points[pair[1]],
+            current_time,
+            options,
+            overlay,
+        )?;
+
+        current_time += leg.duration() as u32;
+        for segment in leg.segments {
+            itinerary.push(segment);
+        }
+    }
+
+    Ok(itinerary)
+}
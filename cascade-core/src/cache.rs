@@ -0,0 +1,200 @@
+//! Persistent on-disk cache for a built [`TransitGraph`].
+//!
+//! Building a `TransitGraph` means reparsing the whole GTFS feed and OSM
+//! extract, which takes multiple seconds even for a mid-sized city. Most of
+//! the time the inputs haven't changed between runs, so
+//! [`TransitGraph::from_cached`] hashes the inputs that determine the
+//! graph's contents and serves a previously-built graph straight from disk
+//! when the hash still matches, rebuilding (and re-caching) only when it
+//! doesn't. [`TransitGraph::save`]/[`TransitGraph::load`] expose the
+//! underlying serialization directly for callers who want to manage their
+//! own cache paths and invalidation instead.
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::graph::{FeedArgs, TransitGraph};
+use crate::Error;
+
+/// Bumped whenever `TransitGraph`'s on-disk representation changes, so that
+/// caches written by an older version of cascade are rejected instead of
+/// being deserialized into the wrong shape.
+const CACHE_FORMAT_VERSION: u32 = 1;
+
+/// On-disk envelope written by [`write_cache`]. Carried separately from
+/// [`CachedGraph`] so writing can serialize `&TransitGraph` without cloning
+/// the graph just to own it.
+#[derive(Serialize)]
+struct CacheEnvelope<'a> {
+    format_version: u32,
+    fingerprint: [u8; 32],
+    graph: &'a TransitGraph,
+}
+
+/// Owned counterpart of [`CacheEnvelope`], used to read a cache file back.
+#[derive(Deserialize)]
+struct CachedGraph {
+    format_version: u32,
+    fingerprint: [u8; 32],
+    graph: TransitGraph,
+}
+
+impl TransitGraph {
+    /// Builds a `TransitGraph` for `feed_args`, using a persistent cache
+    /// under `cache_dir` to skip rebuilding when the inputs haven't changed.
+    ///
+    /// The cache is keyed by a blake3 content hash of every GTFS feed file's
+    /// bytes, the PBF file's bytes, the `departure`/`duration`/`weekday`/
+    /// `service_date` window and the `walk_speed`/`max_transfer_distance`
+    /// parameters `feed_args` describes. If a cache file for that hash exists
+    /// and was written by this version of cascade, it's deserialized and
+    /// returned directly; otherwise the graph is built via
+    /// [`TransitGraph::from`] and the result is written to the cache for
+    /// next time.
+    ///
+    /// # Errors
+    /// Returns an error if the inputs can't be hashed (e.g. a missing
+    /// GTFS/PBF path), if the graph has to be rebuilt and building fails, or
+    /// if the rebuilt graph can't be written to `cache_dir`.
+    pub fn from_cached(feed_args: FeedArgs, cache_dir: &Path) -> Result<Self, Error> {
+        let fingerprint = fingerprint(&feed_args)?;
+        let cache_path = cache_file_path(cache_dir, fingerprint);
+
+        if let Some(graph) = read_cache(&cache_path, fingerprint) {
+            return Ok(graph);
+        }
+
+        let graph = Self::from(feed_args)?;
+        write_cache(&graph, &cache_path, fingerprint)?;
+
+        Ok(graph)
+    }
+
+    /// Serializes this graph to `path` in the same compact binary format
+    /// used by [`Self::from_cached`]'s cache files, but without a
+    /// hash header — a plain save/restore round trip for callers that want
+    /// to manage their own cache invalidation.
+    ///
+    /// # Errors
+    /// Returns an error if `path` can't be created or written to.
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let file = File::create(path)?;
+        bincode::serialize_into(BufWriter::new(file), self)
+            .map_err(|e| Error::InvalidData(format!("failed to write graph to {path:?}: {e}")))?;
+
+        Ok(())
+    }
+
+    /// Deserializes a `TransitGraph` previously written by [`Self::save`].
+    ///
+    /// # Errors
+    /// Returns an error if `path` can't be read, or its contents aren't a
+    /// valid serialized `TransitGraph`.
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let file = File::open(path)?;
+        bincode::deserialize_from(BufReader::new(file))
+            .map_err(|e| Error::InvalidData(format!("failed to read graph from {path:?}: {e}")))
+    }
+}
+
+/// Hashes the inputs that determine a `TransitGraph`'s contents: the bytes
+/// of every file in the GTFS feed directory, the PBF file's bytes, the
+/// `departure`/`duration`/`weekday`/`service_date` window, and the
+/// `walk_speed`/`max_transfer_distance` walking parameters, using blake3 so a
+/// content edit is always detected regardless of file timestamps.
+fn fingerprint(feed_args: &FeedArgs) -> Result<[u8; 32], Error> {
+    let mut hasher = blake3::Hasher::new();
+
+    hash_path_contents(&feed_args.gtfs_path, &mut hasher)?;
+    hash_path_contents(&feed_args.pbf_path, &mut hasher)?;
+    hasher.update(&feed_args.departure.to_le_bytes());
+    hasher.update(&feed_args.duration.to_le_bytes());
+    hasher.update(feed_args.weekday.as_bytes());
+    hasher.update(feed_args.service_date.unwrap_or_default().as_bytes());
+    hasher.update(&feed_args.walk_speed.to_le_bytes());
+    hasher.update(&feed_args.max_transfer_distance.to_le_bytes());
+
+    Ok(*hasher.finalize().as_bytes())
+}
+
+/// Feeds `path`'s bytes into `hasher`, and, if `path` is a directory (e.g. a
+/// GTFS feed folder), every entry's name and bytes as well, in a stable
+/// (sorted-by-name) order so the hash doesn't depend on directory listing
+/// order.
+fn hash_path_contents(path: &Path, hasher: &mut blake3::Hasher) -> Result<(), Error> {
+    let metadata = std::fs::metadata(path)?;
+
+    if metadata.is_dir() {
+        let mut entries = std::fs::read_dir(path)?.collect::<Result<Vec<_>, _>>()?;
+        entries.sort_by_key(std::fs::DirEntry::file_name);
+
+        for entry in entries {
+            hasher.update(entry.file_name().to_string_lossy().as_bytes());
+            hash_file_contents(&entry.path(), hasher)?;
+        }
+    } else {
+        hash_file_contents(path, hasher)?;
+    }
+
+    Ok(())
+}
+
+fn hash_file_contents(path: &Path, hasher: &mut blake3::Hasher) -> Result<(), Error> {
+    let mut file = BufReader::new(File::open(path)?);
+    let mut buffer = [0_u8; 64 * 1024];
+
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+
+    Ok(())
+}
+
+fn cache_file_path(cache_dir: &Path, fingerprint: [u8; 32]) -> PathBuf {
+    let hex: String = fingerprint.iter().map(|byte| format!("{byte:02x}")).collect();
+    cache_dir.join(format!("{hex}.cgraph"))
+}
+
+/// Reads and validates the cache file at `cache_path`, returning `None` (and
+/// silently falling back to a rebuild) on any I/O error, format mismatch or
+/// fingerprint mismatch.
+fn read_cache(cache_path: &Path, expected_fingerprint: [u8; 32]) -> Option<TransitGraph> {
+    let file = File::open(cache_path).ok()?;
+    let cached: CachedGraph = bincode::deserialize_from(BufReader::new(file)).ok()?;
+
+    if cached.format_version != CACHE_FORMAT_VERSION || cached.fingerprint != expected_fingerprint
+    {
+        return None;
+    }
+
+    Some(cached.graph)
+}
+
+fn write_cache(graph: &TransitGraph, cache_path: &Path, fingerprint: [u8; 32]) -> Result<(), Error> {
+    if let Some(parent) = cache_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let envelope = CacheEnvelope {
+        format_version: CACHE_FORMAT_VERSION,
+        fingerprint,
+        graph,
+    };
+
+    let file = File::create(cache_path)?;
+    bincode::serialize_into(BufWriter::new(file), &envelope)
+        .map_err(|e| Error::InvalidData(format!("failed to write graph cache: {e}")))?;
+
+    Ok(())
+}
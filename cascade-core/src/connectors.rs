@@ -5,7 +5,7 @@ use rstar::primitives::GeomWithData;
 use rstar::RTree;
 
 use crate::graph::{GraphEdge, GraphNode, TransitGraph, WalkEdge};
-use crate::{Error, WALK_SPEED};
+use crate::Error;
 
 /// Any object that can be snapped to the nearest node in the graph.
 /// The object should have a geometry method that returns its representation as `geo::Point`.
@@ -32,14 +32,67 @@ pub struct SnappedPoint {
 
 impl SnappedPoint {
     pub fn init(geometry: Point, graph: &TransitGraph) -> Result<SnappedPoint, Error> {
-        let rtree = graph
-            .rtree_ref()
-            .ok_or_else(|| Error::MissingValue("Graph spatial index is not set".to_string()))?;
+        let rtree = graph.rtree_ref();
 
-        let point = snap_single_point(&geometry, rtree)?;
+        let point = snap_single_point(&geometry, rtree, graph.walk_speed())?;
         Ok(point)
     }
 
+    /// Snaps `geometry` to its `k` nearest nodes in the graph instead of just
+    /// the single closest one, for callers that want to consider several
+    /// walk-access points (e.g. every stop within walking distance of a
+    /// query point) rather than being forced through one nearest-node link.
+    ///
+    /// Results are ordered nearest-first. Returns fewer than `k` points if
+    /// the graph has fewer than `k` nodes; returns an empty `Vec` (not an
+    /// error) if the graph has no nodes at all.
+    #[must_use]
+    pub fn init_knn(geometry: Point, graph: &TransitGraph, k: usize) -> Vec<SnappedPoint> {
+        let rtree = graph.rtree_ref();
+        let walk_speed = graph.walk_speed();
+
+        rtree
+            .nearest_neighbor_iter(&geometry)
+            .take(k)
+            .filter_map(|indexed_point| {
+                let index = indexed_point.data?;
+                let distance_meters = geometry.haversine_distance(indexed_point.geom());
+                Some(SnappedPoint {
+                    geometry,
+                    index,
+                    distance: distance_meters / walk_speed,
+                })
+            })
+            .collect()
+    }
+
+    /// Snaps `geometry` to every node in the graph within `radius_meters`,
+    /// like [`Self::init_knn`] but bounded by distance rather than count.
+    ///
+    /// Results are ordered nearest-first. Returns an empty `Vec` if no node
+    /// falls within the radius.
+    #[must_use]
+    pub fn init_within(geometry: Point, graph: &TransitGraph, radius_meters: f64) -> Vec<SnappedPoint> {
+        let rtree = graph.rtree_ref();
+        let walk_speed = graph.walk_speed();
+
+        rtree
+            .nearest_neighbor_iter(&geometry)
+            .map_while(|indexed_point| {
+                let distance_meters = geometry.haversine_distance(indexed_point.geom());
+                if distance_meters > radius_meters {
+                    return None;
+                }
+                let index = indexed_point.data?;
+                Some(SnappedPoint {
+                    geometry,
+                    index,
+                    distance: distance_meters / walk_speed,
+                })
+            })
+            .collect()
+    }
+
     #[must_use]
     pub fn index(&self) -> &NodeIndex {
         &self.index
@@ -55,14 +108,14 @@ impl SnappedPoint {
 /// Required to store the node index to be able to connect the transit nodes to the nearest walk nodes.
 pub type IndexedPoint = GeomWithData<Point, Option<NodeIndex>>;
 
-/// Helper function to find the nearest point in the `RTree` and calculate the distance.
-/// Returns a tuple of the nearest node index and the calculated distance.
-fn find_nearest_point_and_calculate_distance(
+/// Helper function to find the nearest point in the `RTree` and calculate the distance to it.
+/// Returns a tuple of the nearest node index and the distance to it, in meters.
+fn find_nearest_node_and_distance(
     point: &Point,
     tree: &RTree<IndexedPoint>,
 ) -> Result<(NodeIndex, f64), Error> {
     if let Some(nearest_point) = tree.nearest_neighbor(point) {
-        let distance = point.haversine_distance(nearest_point.geom()) / WALK_SPEED;
+        let distance = point.haversine_distance(nearest_point.geom());
         let node = nearest_point.data.ok_or_else(|| {
             Error::NodeNotFound(format!("Nearest node not found for point {point:?}"))
         })?;
@@ -74,17 +127,19 @@ fn find_nearest_point_and_calculate_distance(
     }
 }
 
-/// Snaps a single point to the nearest node in the `RTree`.
+/// Snaps a single point to the nearest node in the `RTree`, weighting the
+/// snap distance at `walk_speed` meters/second.
 pub(crate) fn snap_single_point<T: Snappable>(
     point: &T,
     tree: &RTree<IndexedPoint>,
+    walk_speed: f64,
 ) -> Result<SnappedPoint, Error> {
-    let (index, distance) = find_nearest_point_and_calculate_distance(point.geometry(), tree)?;
+    let (index, distance_meters) = find_nearest_node_and_distance(point.geometry(), tree)?;
 
     Ok(SnappedPoint {
         geometry: *point.geometry(),
         index,
-        distance,
+        distance: distance_meters / walk_speed,
     })
 }
 
@@ -102,8 +157,13 @@ pub(crate) fn build_rtree(graph: &DiGraph<GraphNode, GraphEdge>) -> RTree<Indexe
 }
 
 /// Connects Transit nodes (stops) to the nearest walk nodes.
+/// Stops farther than the graph's configured `max_transfer_distance` from
+/// the nearest walk node are left disconnected rather than creating an
+/// unrealistically long transfer edge.
 pub(crate) fn connect_stops_to_streets(graph: &mut TransitGraph) -> Result<(), Error> {
-    let rtree: RTree<IndexedPoint> = graph.rtree_ref().unwrap().clone();
+    let rtree: RTree<IndexedPoint> = graph.rtree_ref().clone();
+    let walk_speed = graph.walk_speed();
+    let max_transfer_distance = graph.max_transfer_distance();
 
     for node in graph.node_indices() {
         // check if there is already a transfer edge
@@ -122,11 +182,16 @@ pub(crate) fn connect_stops_to_streets(graph: &mut TransitGraph) -> Result<(), E
             .ok_or_else(|| Error::MissingValue("Node weight not found".to_string()))?;
 
         if let GraphNode::Transit(_) = weight {
-            if let Ok((nearest_point_index, distance)) =
-                find_nearest_point_and_calculate_distance(weight.geometry(), &rtree)
+            if let Ok((nearest_point_index, distance_meters)) =
+                find_nearest_node_and_distance(weight.geometry(), &rtree)
             {
+                if distance_meters > max_transfer_distance {
+                    continue;
+                }
+
                 let edge = GraphEdge::Transfer(WalkEdge {
-                    edge_weight: distance,
+                    edge_weight: distance_meters / walk_speed,
+                    geometry: None,
                 });
 
                 graph.add_edge(node, nearest_point_index, edge.clone());
@@ -192,12 +257,13 @@ mod tests {
         let rtree = build_rtree(&graph);
 
         let point = Point::new(0.4, 0.4);
-        let snapped_point = snap_single_point(&point, &rtree).unwrap();
+        let walk_speed = crate::WALK_SPEED;
+        let snapped_point = snap_single_point(&point, &rtree, walk_speed).unwrap();
         assert_eq!(snapped_point.geometry, point);
         assert_eq!(*snapped_point.index(), node1);
         assert!(
             (*snapped_point.distance()
-                - point.haversine_distance(&Point::new(0.0, 0.0)) / WALK_SPEED)
+                - point.haversine_distance(&Point::new(0.0, 0.0)) / walk_speed)
                 .abs()
                 < f64::EPSILON
         );
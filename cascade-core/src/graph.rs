@@ -4,7 +4,7 @@ This module defines the `TransitGraph` and related structures for creating and m
 # Structs
 - `FeedArgs`: Stores the arguments required to create a `TransitGraph` from a GTFS feed.
 - `TransitGraph`: Stores the graph and associated methods. The graph is backed by `petgraph::graph::DiGraph`.
-- `TransitNode`: Represents a transit stop with a `stop_id` and `geometry`.
+- `TransitNode`: Represents a transit stop with a `stop_id`, `geometry`, and `wheelchair_boarding` flag.
 - `WalkNode`: Represents a walkable location with an `id` and `geometry`.
 - `TransitEdge`: Represents a transit connection between two stops.
 - `WalkEdge`: Represents a pedestrian connection.
@@ -13,21 +13,31 @@ This module defines the `TransitGraph` and related structures for creating and m
 # Enums
 - `GraphNode`: Represents the type of node in the graph (`Transit` or `Walk`).
 - `GraphEdge`: Represents the type of edge in the graph (`Transit`, `Transfer`, or `Walk`).
+
+Stop and route identifiers from the GTFS feed are interned into [`StopId`]/[`RouteId`]
+(see [`crate::interner`]) rather than stored as owned `String`s on every node, edge and
+trip; `TransitGraph` owns the interners and can recover the original strings via
+[`TransitGraph::stop_id_str`] and [`TransitGraph::route_id_str`].
 */
 
-use std::fmt::{Debug, Display};
+use std::fmt::{self, Debug, Display};
 use std::ops::{Deref, DerefMut};
 use std::path::PathBuf;
 
-use geo::Point;
+use geo::prelude::*;
+use geo::{LineString, Point};
 use osm4routing::NodeId;
 use petgraph::graph::DiGraph;
 use petgraph::prelude::{EdgeIndex, NodeIndex};
+use petgraph::visit::EdgeRef;
 use rstar::RTree;
+use serde::{Deserialize, Serialize};
 
 use crate::connectors;
 use crate::connectors::IndexedPoint;
+use crate::interner::{HeadsignId, Interners, RouteId, RouteMeta, StopId, TripId};
 use crate::loaders;
+use crate::realtime::RealtimeOverlay;
 use crate::streets;
 use crate::Error;
 
@@ -37,18 +47,40 @@ use crate::Error;
 /// * `edgelist_path` - Path to the `edgelist` file
 /// * `departure` - Departure time in seconds from midnight
 /// * `duration` - Duration in seconds for which the graph should be created
-/// * `weekday` - Weekday for which the graph should be created
+/// * `weekday` - Weekday for which the graph should be created, used to
+///   select active services from `calendar.txt`. Overridden for the exact
+///   day named by `service_date` when that field is set and the feed ships
+///   `calendar_dates.txt`.
+/// * `service_date` - Concrete service date in `YYYYMMDD` format (GTFS's
+///   `calendar_dates.txt` date format). When set, service exceptions for
+///   this date (`exception_type` `1` adds a service, `2` removes one)
+///   override the `weekday`-derived set of active `service_id`s, so routing
+///   reflects the actual schedule on that day rather than just its weekday.
+///   Ignored if the feed has no `calendar_dates.txt`.
+/// * `cache_dir` - Optional directory for a persistent on-disk graph cache;
+///   when set, callers should build the graph via [`TransitGraph::from_cached`]
+///   instead of [`TransitGraph::from`] so repeated builds for unchanged
+///   inputs are served from disk. See [`crate::cache`].
+/// * `walk_speed` - Pedestrian walking speed in meters/second, used to weight
+///   transfer edges between stops and walk nodes.
+/// * `max_transfer_distance` - Maximum distance in meters a stop may be
+///   connected to a walk node over; candidates farther than this are left
+///   disconnected instead of creating an unrealistic transfer edge.
 pub struct FeedArgs<'a> {
     pub gtfs_path: PathBuf,
     pub pbf_path: PathBuf,
     pub departure: u32,
     pub duration: u32,
     pub weekday: &'a str,
+    pub service_date: Option<&'a str>,
+    pub cache_dir: Option<PathBuf>,
+    pub walk_speed: f64,
+    pub max_transfer_distance: f64,
 }
 
 /// `TransitGraph` struct to store the graph and associated method.
 /// Graph is backed by [`petgraph`] `DiGraph` with `NodeType` and `EdgeType` as node and edge types respectively.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TransitGraph {
     // graph shouldnt be public but for now
     // idk how to implement all the traits
@@ -57,6 +89,25 @@ pub struct TransitGraph {
     /// Used for snapping points to the nearest node
     /// This tree only keeps the street nodes
     rtree: RTree<IndexedPoint>,
+    /// Every interner (stops, routes, headsigns) and interner-keyed metadata
+    /// owned by this graph.
+    interners: Interners,
+    /// Pedestrian walking speed in meters/second, used to weight transfer
+    /// edges between stops and walk nodes. Defaults to [`crate::WALK_SPEED`]
+    /// until a [`FeedArgs`] is available to override it, e.g. while the
+    /// street graph is still being built from an OSM extract.
+    walk_speed: f64,
+    /// Maximum distance in meters a stop may be connected to a walk node
+    /// over. Defaults to unlimited until a [`FeedArgs`] is available to
+    /// override it.
+    max_transfer_distance: f64,
+    /// Fastest attainable speed, in meters/second, of any edge in the graph.
+    /// Recomputed by [`Self::recompute_max_edge_speed`] once the graph's
+    /// topology is final, and consulted by
+    /// [`crate::algo::dijkstra::time_dependent_a_star`] to turn straight-line
+    /// distance into an admissible lower bound on remaining travel time: no
+    /// edge can possibly cover ground faster than this.
+    max_edge_speed: f64,
 }
 
 impl TransitGraph {
@@ -66,7 +117,137 @@ impl TransitGraph {
         graph: DiGraph<GraphNode, GraphEdge>,
         rtree: RTree<IndexedPoint>,
     ) -> Self {
-        TransitGraph { graph, rtree }
+        TransitGraph {
+            graph,
+            rtree,
+            interners: Interners::new(),
+            walk_speed: crate::WALK_SPEED,
+            max_transfer_distance: f64::INFINITY,
+            max_edge_speed: crate::WALK_SPEED,
+        }
+    }
+
+    /// Returns mutable access to the interners so loaders can intern GTFS ids
+    /// as they build (or extend) the graph, keeping ids stable across feeds
+    /// merged into the same `TransitGraph`.
+    pub(crate) fn interners_mut(&mut self) -> &mut Interners {
+        &mut self.interners
+    }
+
+    /// Overrides the walking speed and maximum transfer distance used when
+    /// connecting stops to walk nodes, e.g. from a [`FeedArgs`] passed to
+    /// [`TransitGraph::from`].
+    pub(crate) fn set_walk_params(&mut self, walk_speed: f64, max_transfer_distance: f64) {
+        self.walk_speed = walk_speed;
+        self.max_transfer_distance = max_transfer_distance;
+    }
+
+    /// Pedestrian walking speed in meters/second used to weight transfer and
+    /// snapping edges for this graph.
+    pub(crate) const fn walk_speed(&self) -> f64 {
+        self.walk_speed
+    }
+
+    /// Maximum distance in meters a stop may be connected to a walk node
+    /// over.
+    pub(crate) const fn max_transfer_distance(&self) -> f64 {
+        self.max_transfer_distance
+    }
+
+    /// Fastest attainable speed, in meters/second, of any edge currently in
+    /// the graph. See [`Self::recompute_max_edge_speed`].
+    pub(crate) const fn max_edge_speed(&self) -> f64 {
+        self.max_edge_speed
+    }
+
+    /// Recomputes [`Self::max_edge_speed`] over every edge currently in the
+    /// graph: a walk/transfer edge's speed is [`Self::walk_speed`], and a
+    /// transit edge's speed is its geometric (haversine) distance divided by
+    /// its fastest scheduled trip's travel time. Called once the graph's
+    /// topology is final, since an admissible heuristic needs an upper bound
+    /// over every edge that could end up on a search frontier.
+    pub(crate) fn recompute_max_edge_speed(&mut self) {
+        self.max_edge_speed = self
+            .graph
+            .edge_references()
+            .filter_map(|edge| {
+                let source = self.graph.node_weight(edge.source())?.geometry();
+                let target = self.graph.node_weight(edge.target())?.geometry();
+                let distance = source.haversine_distance(target);
+
+                let travel_time = match edge.weight() {
+                    GraphEdge::Transit(transit_edge) => transit_edge
+                        .edge_trips
+                        .iter()
+                        .map(|trip| f64::from(trip.arrival_time.saturating_sub(trip.departure_time)))
+                        .fold(f64::INFINITY, f64::min),
+                    GraphEdge::Transfer(walk_edge) | GraphEdge::Walk(walk_edge) => {
+                        walk_edge.edge_weight
+                    }
+                };
+
+                (travel_time > 0.0).then(|| distance / travel_time)
+            })
+            .fold(self.walk_speed, f64::max);
+    }
+
+    /// Resolves an interned [`StopId`] back to the GTFS `stop_id` string.
+    /// # Panics
+    /// Panics if `id` was not interned by this graph.
+    #[must_use]
+    pub fn stop_id_str(&self, id: StopId) -> &str {
+        self.interners.stops.resolve(id.0)
+    }
+
+    /// Resolves an interned [`RouteId`] back to the GTFS `route_id` string.
+    /// # Panics
+    /// Panics if `id` was not interned by this graph.
+    #[must_use]
+    pub fn route_id_str(&self, id: RouteId) -> &str {
+        self.interners.routes.resolve(id.0)
+    }
+
+    /// Resolves an interned [`HeadsignId`] back to the GTFS `trip_headsign` string.
+    /// # Panics
+    /// Panics if `id` was not interned by this graph.
+    #[must_use]
+    pub fn headsign_str(&self, id: HeadsignId) -> &str {
+        self.interners.headsigns.resolve(id.0)
+    }
+
+    /// Resolves an interned [`TripId`] back to the GTFS `trip_id` string.
+    /// # Panics
+    /// Panics if `id` was not interned by this graph.
+    #[must_use]
+    pub fn trip_id_str(&self, id: TripId) -> &str {
+        self.interners.trips.resolve(id.0)
+    }
+
+    /// Looks up the [`StopId`] already interned for the GTFS `stop_id`
+    /// string `value`, without interning a new one. Used to resolve ids
+    /// coming from a GTFS-Realtime feed against the static feed this graph
+    /// was built from.
+    #[must_use]
+    pub fn stop_id(&self, value: &str) -> Option<StopId> {
+        self.interners.stops.get(value).map(StopId)
+    }
+
+    /// Looks up the [`TripId`] already interned for the GTFS `trip_id`
+    /// string `value`, without interning a new one. Used to resolve ids
+    /// coming from a GTFS-Realtime feed against the static feed this graph
+    /// was built from.
+    #[must_use]
+    pub fn trip_id(&self, value: &str) -> Option<TripId> {
+        self.interners.trips.get(value).map(TripId)
+    }
+
+    /// Resolves an interned [`RouteId`] to the `routes.txt` metadata recorded
+    /// for it (`route_short_name`, `route_color`, `route_text_color`).
+    /// # Panics
+    /// Panics if `id` was not interned by this graph.
+    #[must_use]
+    pub(crate) fn route_meta(&self, id: RouteId) -> &RouteMeta {
+        self.interners.route_meta(id)
     }
 
     /// Create a `TransitGraph` from the GTFS feed and walk graph
@@ -95,26 +276,41 @@ impl TransitGraph {
             std::thread::spawn(move || streets::create_graph(&feed_args.pbf_path));
 
         // Prepare the dataframes from the GTFS feed
-        let (stops_df, stop_times_df) = loaders::prepare_dataframes(
+        let prepared = loaders::prepare_dataframes(
             &feed_args.gtfs_path,
             feed_args.departure,
             feed_args.duration,
             feed_args.weekday,
+            feed_args.service_date,
         )?;
 
-        // Construct transit only graph from dataframes
-        let initial_graph = loaders::new_graph(&stops_df, &stop_times_df)?;
-
         let mut walk_graph = walk_graph_handle.join().map_err(|_| {
             Error::ThreadPanicError("Failed to join street graph thread".to_string())
         })??;
 
+        // Construct transit only graph from dataframes, interning stop and
+        // route ids into the walk graph's interners so ids stay stable
+        // whether this feed or a later `extend_with_transit` call wrote them.
+        let interners = walk_graph.interners_mut();
+        let initial_graph = loaders::new_graph(
+            &prepared.stops_df,
+            &prepared.stop_times_df,
+            interners,
+            &prepared.route_meta,
+            &prepared.segment_map,
+            prepared.transfers_df.as_ref(),
+            feed_args.walk_speed,
+        )?;
+
         // Merge the pedestrian graph with the transit graph (without connections)
         loaders::merge_graphs(&mut walk_graph, &initial_graph);
+
+        walk_graph.set_walk_params(feed_args.walk_speed, feed_args.max_transfer_distance);
         // Connect transit stops in graph to walk nodes
         connectors::connect_stops_to_streets(&mut walk_graph)?;
 
         walk_graph.shrink_to_fit();
+        walk_graph.recompute_max_edge_speed();
 
         Ok(walk_graph)
     }
@@ -122,19 +318,33 @@ impl TransitGraph {
     /// Add transit data from another GTFS feed on top of existing graph.
     /// Currntly uses straightforward logic with addition of all stops and
     /// transit edges to initial graph.
+    /// Stops and routes are interned into the same interners as the initial
+    /// feed, so ids already handed out to callers stay valid and ids shared
+    /// between feeds (e.g. the same `stop_id`) collapse to the same `StopId`.
     #[warn(unstable_features)]
     pub fn extend_with_transit(&mut self, feed_args: &FeedArgs) -> Result<(), Error> {
-        let (stops_df, stop_times_df) = loaders::prepare_dataframes(
+        let prepared = loaders::prepare_dataframes(
             &feed_args.gtfs_path,
             feed_args.departure,
             feed_args.duration,
             feed_args.weekday,
+            feed_args.service_date,
         )?;
 
-        let initial_graph = loaders::new_graph(&stops_df, &stop_times_df)?;
+        let interners = self.interners_mut();
+        let initial_graph = loaders::new_graph(
+            &prepared.stops_df,
+            &prepared.stop_times_df,
+            interners,
+            &prepared.route_meta,
+            &prepared.segment_map,
+            prepared.transfers_df.as_ref(),
+            feed_args.walk_speed,
+        )?;
         loaders::merge_graphs(self, &initial_graph);
 
         connectors::connect_stops_to_streets(self)?;
+        self.recompute_max_edge_speed();
 
         Ok(())
     }
@@ -178,16 +388,21 @@ impl DerefMut for TransitGraph {
 /// Node representing a transit stop
 /// Contains the GTFS feed `stop_id` and `geometry` of the stop
 /// `geometry` is a `geo::Geometry` object representing the location of the stop
-#[derive(Debug, Clone, PartialEq)]
+/// `wheelchair_boarding` reflects `stops.txt`'s `wheelchair_boarding` column:
+/// `true` unless the feed explicitly marks the stop as `2` ("not wheelchair
+/// accessible"); a missing column or `0`/`1` value is treated as accessible,
+/// matching GTFS's "no information" default.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TransitNode {
-    pub stop_id: String,
+    pub stop_id: StopId,
     pub geometry: Point,
+    pub wheelchair_boarding: bool,
 }
 
 /// Node representing a walkable location
 /// Contains the `id` and `geometry` of the location
 /// `geometry` is a `geo::Geometry` object representing the location
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct WalkNode {
     pub id: NodeId,
     pub geometry: Point,
@@ -195,7 +410,7 @@ pub struct WalkNode {
 
 /// Enum representing the type of node in the graph
 /// `Transit` for transit stops and `Walk` for walkable locations
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum GraphNode {
     Transit(TransitNode),
     Walk(WalkNode),
@@ -214,43 +429,217 @@ impl GraphNode {
 /// `edge_trips` is a vector of `Trip` objects representing the trips between the stops
 /// `Trip` contains the `departure_time`, `arrival_time`, `route_id`, and `wheelchair_accessible` information
 /// Vector is sorted by `departure_time` at the source stop so it can be used to find the earliest trip
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// `source_stop`/`target_stop` are the interned ids of the stops this edge departs from and arrives at,
+/// used to key a [`RealtimeOverlay`]'s per-`(trip, stop)` delay adjustments
+/// `geometry` is the real shape of the edge between the two stops, if `shapes.txt` covered it
+/// `boarding_wheelchair_accessible` mirrors `source_stop`'s [`TransitNode::wheelchair_boarding`]
+/// at the time this edge was built, so a wheelchair-constrained query can reject boarding here
+/// without a node lookup on every delay/itinerary calculation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TransitEdge {
     pub(crate) edge_trips: Vec<Trip>,
+    pub(crate) source_stop: StopId,
+    pub(crate) target_stop: StopId,
+    pub(crate) geometry: Option<LineString<f64>>,
+    pub(crate) boarding_wheelchair_accessible: bool,
+}
+
+/// Number of seconds in a single service day. GTFS times for trips that run
+/// past midnight are loaded uncapped (e.g. `25:30:00` parses to `91800`), so
+/// `current_time` in a path search can itself legitimately exceed this once
+/// the search has crossed into the next day.
+pub(crate) const SECONDS_PER_DAY: u32 = 86_400;
+
+impl TransitEdge {
+    /// Finds the earliest trip in `edge_trips` departing at or after
+    /// `current_time`. If every trip already departed earlier in the
+    /// current service day, the search first looks for a trip still to come
+    /// today (keyed by `current_time`'s time-of-day, billed to the current
+    /// elapsed day), and only once today's service is fully exhausted does
+    /// it wrap the clock onto the start of the next service day, so an
+    /// itinerary that crosses midnight keeps finding trips instead of
+    /// dead-ending.
+    ///
+    /// Returns the matching trip together with its arrival time translated
+    /// back onto `current_time`'s timeline (a multiple of
+    /// [`SECONDS_PER_DAY`] is added for every service day the search wrapped
+    /// through), so callers can subtract `current_time` from it directly to
+    /// get a travel time.
+    ///
+    /// When `overlay` is attached, the static schedule is treated as a
+    /// starting guess rather than ground truth: a canceled trip is skipped
+    /// in favor of the next one, and a delayed trip's adjusted departure is
+    /// compared against `current_time` directly, so every trip at or after
+    /// the schedule-based search index is walked in order until one that is
+    /// both live and still catchable is found.
+    pub(crate) fn next_trip(
+        &self,
+        current_time: u32,
+        overlay: Option<&RealtimeOverlay>,
+    ) -> Option<(&Trip, u32)> {
+        let trips = &self.edge_trips;
+        let earliest_departure_at_or_after = |time: u32| -> usize {
+            trips
+                .binary_search_by(|trip| trip.departure_time.cmp(&time))
+                .unwrap_or_else(|index| index)
+        };
+
+        let Some(overlay) = overlay else {
+            let index = earliest_departure_at_or_after(current_time);
+            if index < trips.len() {
+                return Some((&trips[index], trips[index].arrival_time));
+            }
+
+            let elapsed_days = current_time / SECONDS_PER_DAY;
+            let time_of_day = current_time % SECONDS_PER_DAY;
+
+            // A trip later in today's own service window, found by its
+            // time-of-day, is still catchable today — no day wrap needed.
+            let today_index = earliest_departure_at_or_after(time_of_day);
+            if today_index < trips.len() {
+                return Some((
+                    &trips[today_index],
+                    trips[today_index].arrival_time + elapsed_days * SECONDS_PER_DAY,
+                ));
+            }
+
+            // Today's service is fully exhausted: wrap onto the next
+            // service day's earliest trip.
+            let next_day_index = earliest_departure_at_or_after(0);
+            return trips.get(next_day_index).map(|trip| {
+                (trip, trip.arrival_time + (elapsed_days + 1) * SECONDS_PER_DAY)
+            });
+        };
+
+        let scan_from = |start_index: usize, day_offset: u32| {
+            trips[start_index..].iter().find_map(|trip| {
+                if overlay.is_canceled(trip.trip_id) {
+                    return None;
+                }
+
+                let departure = overlay
+                    .adjust_departure(trip.trip_id, self.source_stop, trip.departure_time)
+                    + day_offset * SECONDS_PER_DAY;
+                let arrival = overlay
+                    .adjust_arrival(trip.trip_id, self.target_stop, trip.arrival_time)
+                    + day_offset * SECONDS_PER_DAY;
+
+                (departure >= current_time).then_some((trip, arrival))
+            })
+        };
+
+        let index = earliest_departure_at_or_after(current_time);
+        if let Some(found) = scan_from(index, 0) {
+            return Some(found);
+        }
+
+        let elapsed_days = current_time / SECONDS_PER_DAY;
+        let time_of_day = current_time % SECONDS_PER_DAY;
+
+        // A trip later in today's own service window, found by its
+        // time-of-day, is still catchable today — no day wrap needed.
+        let today_index = earliest_departure_at_or_after(time_of_day);
+        if let Some(found) = scan_from(today_index, elapsed_days) {
+            return Some(found);
+        }
+
+        // Today's service is fully exhausted: wrap onto the next service
+        // day's trips.
+        let next_day_index = earliest_departure_at_or_after(0);
+        scan_from(next_day_index, elapsed_days + 1)
+    }
 }
 
 /// Edge representing a pedestrian connection
 /// `edge_weight` is the weight of the edge in seconds
-#[derive(Debug, Copy, Clone, PartialEq)]
+/// `geometry` is the real shape of the edge, if one was available when it was built
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct WalkEdge {
     pub(crate) edge_weight: f64,
+    pub(crate) geometry: Option<LineString<f64>>,
 }
 
 /// Enum representing the type of edge in the graph
 /// `Transit` for transit connections, `Transfer` for pedestrian connections, and `Walk` for walkable connections
 /// `Transit` contains a `TransitEdge` object
 /// `Transfer` and `Walk` both contain a `WalkEdge` object
-#[derive(Clone, PartialEq, Debug)]
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub enum GraphEdge {
     Transit(TransitEdge),
     Transfer(WalkEdge),
     Walk(WalkEdge),
 }
 
+/// Per-query rider constraints consulted by [`GraphEdge::calculate_delay`]
+/// and [`GraphEdge::calculate_itinerary`] so a search settles only on edges
+/// the requesting rider can actually use, instead of filtering infeasible
+/// trips out after the fact once an itinerary has already been built on top
+/// of them.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct QueryOptions {
+    /// Reject transit edges whose next trip is not wheelchair accessible, or
+    /// whose boarding stop's `stops.txt` `wheelchair_boarding` value rules it
+    /// out.
+    pub(crate) wheelchair: bool,
+    /// Reject walk/transfer edges covering more than this distance, in
+    /// meters. `edge_weight` is stored in seconds, so it's converted back to
+    /// a distance via [`crate::WALK_SPEED`] before comparing.
+    pub(crate) max_walk_distance: f64,
+}
+
+impl Default for QueryOptions {
+    fn default() -> Self {
+        Self {
+            wheelchair: false,
+            max_walk_distance: f64::INFINITY,
+        }
+    }
+}
+
 impl GraphEdge {
-    pub(crate) fn calculate_delay(&self, current_time: u32) -> f64 {
+    /// Computes this edge's travel time at `current_time`, or `f64::INFINITY`
+    /// if it cannot be used at all — either because no trip is running, or
+    /// because `options` rules it out for the requesting rider.
+    pub(crate) fn calculate_delay(
+        &self,
+        current_time: u32,
+        overlay: Option<&RealtimeOverlay>,
+        options: &QueryOptions,
+    ) -> f64 {
         match self {
             Self::Transit(transit_edge) => {
-                let trips = &transit_edge.edge_trips;
-                match trips.binary_search_by(|trip| trip.departure_time.cmp(&current_time)) {
-                    Ok(index) | Err(index) if index < trips.len() => {
-                        f64::from(trips[index].arrival_time - current_time)
+                if options.wheelchair && !transit_edge.boarding_wheelchair_accessible {
+                    return f64::INFINITY;
+                }
+                match transit_edge.next_trip(current_time, overlay) {
+                    Some((trip, _)) if options.wheelchair && !trip.wheelchair_accessible => {
+                        f64::INFINITY
                     }
-                    // No trip found after current time, skip this edge
-                    _ => f64::INFINITY,
+                    // An overlay can adjust departure and arrival independently, so a
+                    // well-formed but internally-inconsistent feed (e.g. a large positive
+                    // departure delay paired with a negative arrival delay) can leave
+                    // `arrival_time` below `current_time`; saturate instead of underflowing.
+                    Some((_, arrival_time)) => f64::from(arrival_time.saturating_sub(current_time)),
+                    // No trip found today or on the next service day, skip this edge
+                    None => f64::INFINITY,
+                }
+            }
+            Self::Transfer(walk_edge) | Self::Walk(walk_edge) => {
+                if walk_edge.edge_weight * crate::WALK_SPEED > options.max_walk_distance {
+                    f64::INFINITY
+                } else {
+                    walk_edge.edge_weight
                 }
             }
-            Self::Transfer(walk_edge) | Self::Walk(walk_edge) => walk_edge.edge_weight,
+        }
+    }
+
+    /// Returns this edge's real shape geometry, if one was available when the
+    /// edge was built (e.g. from `shapes.txt` or the OSM street network).
+    pub(crate) fn geometry(&self) -> Option<&LineString<f64>> {
+        match self {
+            Self::Transit(transit_edge) => transit_edge.geometry.as_ref(),
+            Self::Transfer(walk_edge) | Self::Walk(walk_edge) => walk_edge.geometry.as_ref(),
         }
     }
 }
@@ -261,27 +650,36 @@ impl GraphEdge {
 /// `arrival_time` is the time the trip arrives at the next stop
 /// `route_id` is the identifier for the route
 /// `wheelchair_accessible` is a boolean indicating if the trip is wheelchair accessible
+/// `trip_headsign` is the interned `trip_headsign` from `trips.txt`, if the feed provided one
+/// `trip_id` is the interned `trip_id` from `trips.txt`, used to key a [`RealtimeOverlay`]'s
+/// per-trip delay adjustments and cancellations
 /// `Trip` is used for pathfinding in the graph
-#[derive(Debug, Clone, Eq)]
+#[derive(Debug, Clone, Copy, Eq, Serialize, Deserialize)]
 pub(crate) struct Trip {
-    departure_time: u32,
-    arrival_time: u32,
-    route_id: String,
-    wheelchair_accessible: bool,
+    pub(crate) departure_time: u32,
+    pub(crate) arrival_time: u32,
+    pub(crate) route_id: RouteId,
+    pub(crate) wheelchair_accessible: bool,
+    pub(crate) trip_headsign: Option<HeadsignId>,
+    pub(crate) trip_id: TripId,
 }
 
 impl Trip {
     pub(crate) const fn new(
         departure_time: u32,
         arrival_time: u32,
-        route_id: String,
+        route_id: RouteId,
         wheelchair_accessible: bool,
+        trip_headsign: Option<HeadsignId>,
+        trip_id: TripId,
     ) -> Self {
         Self {
             departure_time,
             arrival_time,
             route_id,
             wheelchair_accessible,
+            trip_headsign,
+            trip_id,
         }
     }
 }
@@ -305,12 +703,33 @@ impl Ord for Trip {
     }
 }
 
-impl Display for Trip {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl Trip {
+    /// Returns a [`Display`]-able view of this trip that resolves its
+    /// interned `route_id` back to the GTFS string via `graph`.
+    #[must_use]
+    pub(crate) const fn display_with<'a>(&'a self, graph: &'a TransitGraph) -> TripDisplay<'a> {
+        TripDisplay { trip: self, graph }
+    }
+}
+
+/// Formats a [`Trip`] with its `route_id` resolved through the owning
+/// `TransitGraph`'s interner. See [`Trip::display_with`].
+pub(crate) struct TripDisplay<'a> {
+    trip: &'a Trip,
+    graph: &'a TransitGraph,
+}
+
+impl Display for TripDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let trip = self.trip;
         write!(
             f,
-            "[arrival_time: {}, departure_time: {}, route_id: {}, wheelchair_accessible: {}]",
-            self.arrival_time, self.departure_time, self.route_id, self.wheelchair_accessible
+            "[arrival_time: {}, departure_time: {}, route_id: {}, wheelchair_accessible: {}, trip_headsign: {}]",
+            trip.arrival_time,
+            trip.departure_time,
+            self.graph.route_id_str(trip.route_id),
+            trip.wheelchair_accessible,
+            trip.trip_headsign.map_or("", |id| self.graph.headsign_str(id))
         )
     }
 }
@@ -324,28 +743,136 @@ mod tests {
     fn test_calculate_delay_transit() {
         let edge = GraphEdge::Transit(TransitEdge {
             edge_trips: vec![
-                Trip::new(0, 10, "route1".to_string(), false),
-                Trip::new(15, 20, "route2".to_string(), true),
-                Trip::new(25, 30, "route3".to_string(), false),
+                Trip::new(0, 10, RouteId(0), false, None, TripId(0)),
+                Trip::new(15, 20, RouteId(1), true, None, TripId(1)),
+                Trip::new(25, 30, RouteId(2), false, None, TripId(2)),
             ],
+            source_stop: StopId(0),
+            target_stop: StopId(1),
+            geometry: None,
+            boarding_wheelchair_accessible: true,
         });
 
-        assert!(approx::abs_diff_eq!(edge.calculate_delay(0), 10.0));
-        assert!(approx::abs_diff_eq!(edge.calculate_delay(5), 15.0));
-        assert!(approx::abs_diff_eq!(edge.calculate_delay(10), 10.0));
-        assert!(approx::abs_diff_eq!(edge.calculate_delay(15), 5.0));
-        assert!(approx::abs_diff_eq!(edge.calculate_delay(20), 10.0));
-        assert!(approx::abs_diff_eq!(edge.calculate_delay(25), 5.0));
-        assert_eq!(edge.calculate_delay(30), f64::INFINITY);
-        assert_eq!(edge.calculate_delay(35), f64::INFINITY);
+        assert!(approx::abs_diff_eq!(edge.calculate_delay(0, None, &QueryOptions::default()), 10.0));
+        assert!(approx::abs_diff_eq!(edge.calculate_delay(5, None, &QueryOptions::default()), 15.0));
+        assert!(approx::abs_diff_eq!(edge.calculate_delay(10, None, &QueryOptions::default()), 10.0));
+        assert!(approx::abs_diff_eq!(edge.calculate_delay(15, None, &QueryOptions::default()), 5.0));
+        assert!(approx::abs_diff_eq!(edge.calculate_delay(20, None, &QueryOptions::default()), 10.0));
+        assert!(approx::abs_diff_eq!(edge.calculate_delay(25, None, &QueryOptions::default()), 5.0));
+        // Past the last trip of the day, the search wraps to tomorrow's first
+        // trip, arriving at 10 + SECONDS_PER_DAY.
+        assert_eq!(edge.calculate_delay(30, None, &QueryOptions::default()), 86380.0);
+        assert_eq!(edge.calculate_delay(35, None, &QueryOptions::default()), 86375.0);
     }
 
     #[test]
     fn test_calculate_delay_walk() {
-        let edge = GraphEdge::Walk(WalkEdge { edge_weight: 2.5 });
+        let edge = GraphEdge::Walk(WalkEdge {
+            edge_weight: 2.5,
+            geometry: None,
+        });
+
+        assert!(approx::abs_diff_eq!(edge.calculate_delay(0, None, &QueryOptions::default()), 2.5));
+        assert!(approx::abs_diff_eq!(edge.calculate_delay(10, None, &QueryOptions::default()), 2.5));
+        assert!(approx::abs_diff_eq!(edge.calculate_delay(100, None, &QueryOptions::default()), 2.5));
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn test_calculate_delay_transit_with_overlay() {
+        let edge = GraphEdge::Transit(TransitEdge {
+            edge_trips: vec![
+                Trip::new(0, 10, RouteId(0), false, None, TripId(0)),
+                Trip::new(15, 20, RouteId(1), true, None, TripId(1)),
+            ],
+            source_stop: StopId(0),
+            target_stop: StopId(1),
+            geometry: None,
+            boarding_wheelchair_accessible: true,
+        });
+
+        let mut overlay = RealtimeOverlay::new();
+        overlay.set_cancelled(TripId(0));
+        overlay.set_delay(TripId(1), StopId(0), 10, 0);
+
+        // Trip 0 is cancelled, so trip 1 is the next candidate; its
+        // departure is delayed by 10s (to 25) but its arrival is unaffected,
+        // and it's still catchable from 5.
+        assert!(approx::abs_diff_eq!(
+            edge.calculate_delay(5, Some(&overlay), &QueryOptions::default()),
+            15.0
+        ));
+        // Once the delayed departure has passed, the search wraps to the next
+        // service day, where trip 0 is still cancelled and trip 1 arrives at
+        // 20 + SECONDS_PER_DAY.
+        assert_eq!(edge.calculate_delay(30, Some(&overlay), &QueryOptions::default()), 86390.0);
+    }
+
+    #[test]
+    fn test_next_trip_wraps_to_next_service_day_past_last_trip() {
+        let edge = TransitEdge {
+            edge_trips: vec![
+                Trip::new(21600, 22000, RouteId(0), false, None, TripId(0)),
+                Trip::new(84000, 84600, RouteId(1), false, None, TripId(1)),
+            ],
+            source_stop: StopId(0),
+            target_stop: StopId(1),
+            geometry: None,
+            boarding_wheelchair_accessible: true,
+        };
+
+        // Every trip has already departed earlier today; the rider must be
+        // routed to tomorrow's first trip instead of finding nothing.
+        let (trip, arrival) = edge.next_trip(85000, None).expect("should wrap to tomorrow");
+        assert_eq!(trip.trip_id, TripId(0));
+        assert_eq!(arrival, 22000 + SECONDS_PER_DAY);
+    }
+
+    #[test]
+    fn test_next_trip_matches_later_today_without_overcounting_day_offset() {
+        let edge = TransitEdge {
+            edge_trips: vec![
+                Trip::new(3600, 4200, RouteId(0), false, None, TripId(0)),
+                Trip::new(84000, 84600, RouteId(1), false, None, TripId(1)),
+            ],
+            source_stop: StopId(0),
+            target_stop: StopId(1),
+            geometry: None,
+            boarding_wheelchair_accessible: true,
+        };
+
+        // `current_time` has already wrapped a full day ahead (elapsed_days
+        // == 1); the 3600 trip is still catchable *today*, so the returned
+        // arrival must be billed to one day, not two.
+        let (trip, arrival) = edge
+            .next_trip(90000, None)
+            .expect("should match today's later trip");
+        assert_eq!(trip.trip_id, TripId(0));
+        assert_eq!(arrival, 4200 + SECONDS_PER_DAY);
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn test_calculate_delay_transit_with_inconsistent_overlay_delays() {
+        let edge = GraphEdge::Transit(TransitEdge {
+            edge_trips: vec![Trip::new(50, 60, RouteId(0), false, None, TripId(0))],
+            source_stop: StopId(0),
+            target_stop: StopId(1),
+            geometry: None,
+            boarding_wheelchair_accessible: true,
+        });
 
-        assert!(approx::abs_diff_eq!(edge.calculate_delay(0), 2.5));
-        assert!(approx::abs_diff_eq!(edge.calculate_delay(10), 2.5));
-        assert!(approx::abs_diff_eq!(edge.calculate_delay(100), 2.5));
+        let mut overlay = RealtimeOverlay::new();
+        // A large positive departure delay at the source stop keeps the trip
+        // catchable, while a large negative arrival delay at the target stop
+        // pulls its adjusted arrival below `current_time` — a well-formed
+        // but internally-inconsistent feed.
+        overlay.set_delay(TripId(0), StopId(0), 1000, 0);
+        overlay.set_delay(TripId(0), StopId(1), 0, -1000);
+
+        assert_eq!(
+            edge.calculate_delay(40, Some(&overlay), &QueryOptions::default()),
+            0.0
+        );
     }
 }
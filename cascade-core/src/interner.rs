@@ -0,0 +1,161 @@
+//! String interning for GTFS identifiers.
+//!
+//! GTFS feeds reference stops and routes by string ids (`stop_id`, `route_id`)
+//! that are repeated across thousands of trips and edges. Interning them once
+//! into small `u32` indexes avoids duplicating and re-hashing the same strings
+//! throughout the graph.
+
+use ahash::{HashMap, HashMapExt};
+use serde::{Deserialize, Serialize};
+
+/// Typed index into the stop-id [`Interner`] owned by `TransitGraph`.
+///
+/// Any `StopId` in circulation is guaranteed to have been produced by
+/// [`Interner::intern`], so it always has a corresponding string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct StopId(pub(crate) u32);
+
+/// Typed index into the route-id [`Interner`] owned by `TransitGraph`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct RouteId(pub(crate) u32);
+
+/// Typed index into the trip-headsign [`Interner`] owned by `TransitGraph`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct HeadsignId(pub(crate) u32);
+
+/// Typed index into the trip-id [`Interner`] owned by `TransitGraph`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct TripId(pub(crate) u32);
+
+/// Bidirectional string interner backed by a `Vec<String>` for id -> string
+/// lookups and a `HashMap<String, u32>` for string -> id lookups.
+///
+/// Ids are assigned sequentially starting at `0`, so the `Vec` index always
+/// matches the interned `u32`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct Interner {
+    strings: Vec<String>,
+    index: HashMap<String, u32>,
+}
+
+impl Interner {
+    pub(crate) fn new() -> Self {
+        Self {
+            strings: Vec::new(),
+            index: HashMap::new(),
+        }
+    }
+
+    /// Interns `value`, returning the existing id if `value` was seen before
+    /// or allocating a new one otherwise.
+    pub(crate) fn intern(&mut self, value: &str) -> u32 {
+        if let Some(&id) = self.index.get(value) {
+            return id;
+        }
+
+        let id = u32::try_from(self.strings.len()).expect("interner overflowed u32 ids");
+        self.strings.push(value.to_string());
+        self.index.insert(value.to_string(), id);
+        id
+    }
+
+    /// Resolves a previously interned id back to its string.
+    /// # Panics
+    /// Panics if `id` was not produced by this interner's [`Interner::intern`].
+    pub(crate) fn resolve(&self, id: u32) -> &str {
+        self.strings
+            .get(id as usize)
+            .unwrap_or_else(|| panic!("interner does not contain id {id}"))
+    }
+
+    /// Looks up `value`'s id without interning it, returning `None` if it was
+    /// never seen. Used to resolve ids coming from a source that must not
+    /// mint new ids of its own, e.g. a GTFS-Realtime feed matched against the
+    /// static feed's already-built interners.
+    pub(crate) fn get(&self, value: &str) -> Option<u32> {
+        self.index.get(value).copied()
+    }
+}
+
+/// Metadata from `routes.txt`, looked up by [`RouteId`] alongside the
+/// route's interned `route_id` string.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct RouteMeta {
+    pub(crate) route_short_name: String,
+    pub(crate) route_color: Option<String>,
+    pub(crate) route_text_color: Option<String>,
+}
+
+/// Bundles every interner (and interner-keyed metadata) `TransitGraph` needs
+/// while building or extending itself from a GTFS feed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct Interners {
+    pub(crate) stops: Interner,
+    pub(crate) routes: Interner,
+    pub(crate) headsigns: Interner,
+    pub(crate) trips: Interner,
+    /// Metadata for each interned route, indexed by its `RouteId`; grown in
+    /// lockstep with `routes` by [`Interners::intern_route`].
+    route_meta: Vec<RouteMeta>,
+}
+
+impl Interners {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Interns `route_id`, recording `meta` the first time this route is
+    /// seen. `meta` is only evaluated on first sight of `route_id`, so later
+    /// feeds re-using the same `route_id` keep the metadata recorded for it
+    /// originally.
+    pub(crate) fn intern_route(&mut self, route_id: &str, meta: impl FnOnce() -> RouteMeta) -> RouteId {
+        let id = self.routes.intern(route_id);
+        let index = id as usize;
+        if index == self.route_meta.len() {
+            self.route_meta.push(meta());
+        }
+        RouteId(id)
+    }
+
+    /// Resolves an interned [`RouteId`] back to its recorded [`RouteMeta`].
+    /// # Panics
+    /// Panics if `id` was not produced by [`Interners::intern_route`].
+    pub(crate) fn route_meta(&self, id: RouteId) -> &RouteMeta {
+        self.route_meta
+            .get(id.0 as usize)
+            .unwrap_or_else(|| panic!("interner does not contain route metadata for {id:?}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_intern_deduplicates() {
+        let mut interner = Interner::new();
+        let a = interner.intern("stop_1");
+        let b = interner.intern("stop_2");
+        let a_again = interner.intern("stop_1");
+
+        assert_eq!(a, a_again);
+        assert_ne!(a, b);
+        assert_eq!(interner.resolve(a), "stop_1");
+        assert_eq!(interner.resolve(b), "stop_2");
+    }
+
+    #[test]
+    fn test_intern_route_records_metadata_once() {
+        let mut interners = Interners::new();
+        let meta = RouteMeta {
+            route_short_name: "55".to_string(),
+            route_color: Some("FF0000".to_string()),
+            route_text_color: None,
+        };
+        let id = interners.intern_route("route_1", || meta.clone());
+        let id_again = interners.intern_route("route_1", || panic!("should not re-run"));
+
+        assert_eq!(id, id_again);
+        assert_eq!(interners.route_meta(id).route_short_name, "55");
+    }
+}
@@ -58,12 +58,15 @@ use polars::prelude::*;
 use thiserror::Error;
 
 pub mod algo;
+pub mod cache;
 pub mod connectors;
 pub mod graph;
+pub mod interner;
 pub mod loaders;
 pub mod prelude;
-pub mod shapes;
+pub mod realtime;
 pub mod streets;
+pub mod trip_geometry;
 
 const WALK_SPEED: f64 = 1.39;
 
@@ -94,6 +97,8 @@ pub enum Error {
     PolarsError(#[from] PolarsError),
     #[error("Thread panicked")]
     ThreadPanicError(String),
+    #[error("Failed to build thread pool: {0}")]
+    ThreadPoolError(String),
 }
 
 impl From<Error> for PolarsError {
@@ -1,7 +1,8 @@
 use std::path::{Path, PathBuf};
 
-use ahash::{HashMap, HashMapExt};
-use geo::Point;
+use ahash::{HashMap, HashMapExt, HashSet, HashSetExt};
+use geo::prelude::*;
+use geo::{LineString, Point};
 use itertools::Itertools;
 use petgraph::graph::{DiGraph, Graph};
 use petgraph::prelude::NodeIndex;
@@ -10,7 +11,9 @@ use polars::chunked_array::ops::SortMultipleOptions;
 use polars::io::csv::read::CsvReadOptions;
 use polars::prelude::*;
 
-use crate::graph::{GraphEdge, GraphNode, TransitEdge, TransitNode, Trip};
+use crate::graph::{GraphEdge, GraphNode, TransitEdge, TransitNode, Trip, WalkEdge};
+use crate::interner::{HeadsignId, Interner, Interners, RouteId, RouteMeta, StopId, TripId};
+use crate::trip_geometry::{self, DEFAULT_PROXIMITY_THRESHOLD_METERS};
 use crate::Error;
 
 fn read_csv(file_path: PathBuf) -> Result<DataFrame, Error> {
@@ -64,6 +67,245 @@ fn filter_by_time(df: &mut DataFrame, departure: u32, duration: u32) -> Result<D
     Ok(df.filter(&mask)?)
 }
 
+fn sec_to_hhmmss(total_seconds: u32) -> String {
+    let hours = total_seconds / 3600;
+    let minutes = (total_seconds % 3600) / 60;
+    let seconds = total_seconds % 60;
+    format!("{hours:02}:{minutes:02}:{seconds:02}")
+}
+
+/// Overrides the weekday-derived `service_id` set (`base_service_ids`, a
+/// single-column `service_id` `DataFrame`) with `calendar_dates.txt`'s
+/// exceptions for `service_date`: a row with `exception_type == 1` adds its
+/// `service_id` even if `calendar.txt` didn't mark it active that weekday, and
+/// `exception_type == 2` removes it even if `calendar.txt` did.
+fn apply_calendar_date_exceptions(
+    base_service_ids: &DataFrame,
+    calendar_dates_df: &DataFrame,
+    service_date: &str,
+) -> Result<DataFrame, Error> {
+    let mut active: HashSet<String> = base_service_ids
+        .column("service_id")?
+        .cast(&DataType::String)?
+        .str()?
+        .iter()
+        .filter_map(|id| id.map(str::to_string))
+        .collect();
+
+    let dates = calendar_dates_df.column("date")?.cast(&DataType::String)?;
+    let service_ids = calendar_dates_df.column("service_id")?.cast(&DataType::String)?;
+    let exception_types = calendar_dates_df.column("exception_type")?.cast(&DataType::Int64)?;
+
+    for ((date, service_id), exception_type) in dates
+        .str()?
+        .iter()
+        .zip(service_ids.str()?.iter())
+        .zip(exception_types.i64()?.iter())
+    {
+        let (Some(date), Some(service_id), Some(exception_type)) = (date, service_id, exception_type)
+        else {
+            continue;
+        };
+
+        if date != service_date {
+            continue;
+        }
+
+        match exception_type {
+            1 => {
+                active.insert(service_id.to_string());
+            }
+            2 => {
+                active.remove(service_id);
+            }
+            _ => {}
+        }
+    }
+
+    let service_id: Vec<String> = active.into_iter().collect();
+    Ok(df! { "service_id" => service_id }?)
+}
+
+/// Expands `frequencies.txt`-driven trips into one concrete, scheduled
+/// instance per headway departure.
+///
+/// A trip listed in `frequencies.txt` has only a single representative run in
+/// `stop_times.txt`, with `(start_time, end_time, headway_secs)` describing
+/// how often that run repeats. For every such trip this replicates its
+/// template `stop_times` rows once per departure in
+/// `[start_time, end_time)` (stepping by `headway_secs`), shifting every stop's
+/// arrival/departure time so the trip's first stop departs exactly at that
+/// instance's start time while preserving the template's internal spacing,
+/// and assigns each instance a distinct synthetic `trip_id` so it behaves as
+/// an independent scheduled trip from here on. `trips_df` gains a duplicate
+/// row (route, service, accessibility, ...) for every synthetic id, keyed off
+/// the original trip's row, so the later `trip_id` join still resolves.
+/// Trips not listed in `frequencies.txt` pass through unchanged.
+fn expand_frequencies(
+    stop_times_df: &DataFrame,
+    trips_df: &DataFrame,
+    frequencies_df: &DataFrame,
+) -> Result<(DataFrame, DataFrame), Error> {
+    let mut windows: HashMap<String, Vec<(u32, u32, u32)>> = HashMap::new();
+
+    let trip_ids = frequencies_df.column("trip_id")?.cast(&DataType::String)?;
+    let start_times = hhmmss_to_sec(frequencies_df.column("start_time")?);
+    let end_times = hhmmss_to_sec(frequencies_df.column("end_time")?);
+    let headways = frequencies_df.column("headway_secs")?.cast(&DataType::UInt32)?;
+
+    for (((trip_id, start), end), headway) in trip_ids
+        .str()?
+        .iter()
+        .zip(start_times.u32()?.iter())
+        .zip(end_times.u32()?.iter())
+        .zip(headways.u32()?.iter())
+    {
+        let (Some(trip_id), Some(start), Some(end), Some(headway)) = (trip_id, start, end, headway)
+        else {
+            continue;
+        };
+        windows.entry(trip_id.to_string()).or_default().push((start, end, headway));
+    }
+
+    if windows.is_empty() {
+        return Ok((stop_times_df.clone(), trips_df.clone()));
+    }
+
+    let templated_trip_ids = stop_times_df.column("trip_id")?.cast(&DataType::String)?;
+    let is_templated: Vec<bool> = templated_trip_ids
+        .str()?
+        .iter()
+        .map(|trip_id| trip_id.is_some_and(|trip_id| windows.contains_key(trip_id)))
+        .collect();
+    let is_passthrough: Vec<bool> = is_templated.iter().map(|templated| !templated).collect();
+
+    let templated_mask: BooleanChunked = is_templated.into_iter().collect();
+    let passthrough_mask: BooleanChunked = is_passthrough.into_iter().collect();
+
+    let passthrough_stop_times = stop_times_df.filter(&passthrough_mask)?.select([
+        "trip_id",
+        "stop_id",
+        "stop_sequence",
+        "arrival_time",
+        "departure_time",
+    ])?;
+    let templated_stop_times = stop_times_df.filter(&templated_mask)?;
+
+    let mut new_trip_ids: Vec<String> = Vec::new();
+    let mut new_stop_ids: Vec<String> = Vec::new();
+    let mut new_stop_sequences: Vec<i64> = Vec::new();
+    let mut new_arrivals: Vec<String> = Vec::new();
+    let mut new_departures: Vec<String> = Vec::new();
+    let mut synthetic_trip_ids: HashMap<String, Vec<String>> = HashMap::new();
+
+    let grouped = templated_stop_times.group_by(["trip_id"])?;
+    grouped.apply(|group| {
+        let sorted_group = group.sort(["stop_sequence"], SortMultipleOptions::default())?;
+
+        let trip_id = sorted_group
+            .column("trip_id")?
+            .cast(&DataType::String)?
+            .str()?
+            .get(0)
+            .ok_or_else(|| Error::MissingValue("trip_id".to_string()))?
+            .to_string();
+
+        let stop_ids = sorted_group.column("stop_id")?.cast(&DataType::String)?;
+        let stop_ids: Vec<String> = stop_ids.str()?.iter().map(|opt| opt.unwrap_or_default().to_string()).collect();
+
+        let stop_sequences = sorted_group.column("stop_sequence")?.cast(&DataType::Int64)?;
+        let stop_sequences: Vec<i64> = stop_sequences.i64()?.iter().map(|opt| opt.unwrap_or_default()).collect();
+
+        let arrival_seconds = hhmmss_to_sec(sorted_group.column("arrival_time")?);
+        let arrival_seconds: Vec<u32> = arrival_seconds.u32()?.iter().map(|opt| opt.unwrap_or_default()).collect();
+        let departure_seconds = hhmmss_to_sec(sorted_group.column("departure_time")?);
+        let departure_seconds: Vec<u32> = departure_seconds.u32()?.iter().map(|opt| opt.unwrap_or_default()).collect();
+
+        let Some(&template_first_departure) = departure_seconds.first() else {
+            return Ok(sorted_group);
+        };
+
+        let mut instance = 0usize;
+        for &(window_start, window_end, headway) in &windows[&trip_id] {
+            let mut instance_start = window_start;
+            while instance_start < window_end {
+                let synthetic_trip_id = format!("{trip_id}__freq{instance}");
+                let shift = i64::from(instance_start) - i64::from(template_first_departure);
+
+                for ((stop_id, stop_sequence), (arrival, departure)) in stop_ids
+                    .iter()
+                    .zip(stop_sequences.iter())
+                    .zip(arrival_seconds.iter().zip(departure_seconds.iter()))
+                {
+                    new_trip_ids.push(synthetic_trip_id.clone());
+                    new_stop_ids.push(stop_id.clone());
+                    new_stop_sequences.push(*stop_sequence);
+                    new_arrivals.push(sec_to_hhmmss((i64::from(*arrival) + shift).max(0) as u32));
+                    new_departures.push(sec_to_hhmmss((i64::from(*departure) + shift).max(0) as u32));
+                }
+
+                synthetic_trip_ids.entry(trip_id.clone()).or_default().push(synthetic_trip_id);
+                instance_start += headway;
+                instance += 1;
+            }
+        }
+
+        Ok(sorted_group)
+    })?;
+
+    let expanded_stop_times = df! {
+        "trip_id" => new_trip_ids,
+        "stop_id" => new_stop_ids,
+        "stop_sequence" => new_stop_sequences,
+        "arrival_time" => new_arrivals,
+        "departure_time" => new_departures,
+    }?;
+
+    let stop_times_df = passthrough_stop_times.vstack(&expanded_stop_times)?;
+    let trips_df = expand_templated_trips(trips_df, &synthetic_trip_ids)?;
+
+    Ok((stop_times_df, trips_df))
+}
+
+/// Duplicates each `trips_df` row named by `synthetic_trip_ids` once per
+/// generated `trip_id`, so every [`expand_frequencies`]-synthesized trip still
+/// resolves to its original route/service/accessibility metadata at the later
+/// `trip_id` join. Trips not named by `synthetic_trip_ids` pass through with
+/// their original row and `trip_id` untouched.
+fn expand_templated_trips(
+    trips_df: &DataFrame,
+    synthetic_trip_ids: &HashMap<String, Vec<String>>,
+) -> Result<DataFrame, Error> {
+    let trip_ids = trips_df.column("trip_id")?.cast(&DataType::String)?;
+    let trip_ids = trip_ids.str()?;
+
+    let mut expanded: Option<DataFrame> = None;
+
+    for (row_index, trip_id) in trip_ids.iter().enumerate() {
+        let Some(trip_id) = trip_id else { continue };
+
+        let Some(new_ids) = synthetic_trip_ids.get(trip_id) else {
+            let row = trips_df.slice(row_index as i64, 1);
+            expanded = Some(match expanded {
+                Some(df) => df.vstack(&row)?,
+                None => row,
+            });
+            continue;
+        };
+
+        for new_id in new_ids {
+            let mut row = trips_df.slice(row_index as i64, 1);
+            row.with_column(Series::new("trip_id".into(), [new_id.as_str()]))?;
+            expanded = Some(match expanded {
+                Some(df) => df.vstack(&row)?,
+                None => row,
+            });
+        }
+    }
+
+    Ok(expanded.unwrap_or_else(|| trips_df.clear()))
+}
+
 fn validate_feed(path: &impl AsRef<Path>) -> Result<(), Error> {
     let path = path.as_ref();
 
@@ -71,7 +313,13 @@ fn validate_feed(path: &impl AsRef<Path>) -> Result<(), Error> {
         return Err(Error::InvalidData("Invalid directory".to_string()));
     }
 
-    let required = ["stops.txt", "trips.txt", "stop_times.txt", "calendar.txt"];
+    let required = [
+        "stops.txt",
+        "trips.txt",
+        "stop_times.txt",
+        "calendar.txt",
+        "routes.txt",
+    ];
 
     for file in required {
         if !path.join(file).exists() {
@@ -84,26 +332,112 @@ fn validate_feed(path: &impl AsRef<Path>) -> Result<(), Error> {
     Ok(())
 }
 
+/// Return type of [`prepare_dataframes`]: the filtered `stops`/`stop_times`
+/// dataframes, `routes.txt` metadata keyed by `route_id`, (if the feed
+/// shipped `shapes.txt`) real edge geometry keyed by the stop pair it spans,
+/// and (if the feed shipped `transfers.txt`) the raw footpath dataframe.
+pub(crate) struct PreparedFeed {
+    pub(crate) stops_df: DataFrame,
+    pub(crate) stop_times_df: DataFrame,
+    pub(crate) route_meta: HashMap<String, RouteMeta>,
+    pub(crate) segment_map: HashMap<(String, String), LineString<f64>>,
+    pub(crate) transfers_df: Option<DataFrame>,
+}
+
+fn read_route_meta(routes_df: &DataFrame) -> Result<HashMap<String, RouteMeta>, Error> {
+    let route_ids = routes_df.column("route_id")?.cast(&DataType::String)?;
+    let route_ids = route_ids.str()?.iter();
+
+    let short_names = routes_df.column("route_short_name")?.cast(&DataType::String)?;
+    let short_names = short_names.str()?.iter();
+
+    let colors: Vec<Option<String>> = match routes_df.column("route_color") {
+        Ok(col) => col
+            .cast(&DataType::String)?
+            .str()?
+            .iter()
+            .map(|opt| opt.map(str::to_string))
+            .collect(),
+        Err(_) => vec![None; routes_df.height()],
+    };
+    let text_colors: Vec<Option<String>> = match routes_df.column("route_text_color") {
+        Ok(col) => col
+            .cast(&DataType::String)?
+            .str()?
+            .iter()
+            .map(|opt| opt.map(str::to_string))
+            .collect(),
+        Err(_) => vec![None; routes_df.height()],
+    };
+
+    let mut route_meta = HashMap::with_capacity(routes_df.height());
+    for (((route_id, short_name), color), text_color) in
+        route_ids.zip(short_names).zip(colors).zip(text_colors)
+    {
+        let route_id = route_id.ok_or_else(|| Error::MissingValue("route_id".to_string()))?;
+        route_meta.insert(
+            route_id.to_string(),
+            RouteMeta {
+                route_short_name: short_name.unwrap_or_default().to_string(),
+                route_color: color,
+                route_text_color: text_color,
+            },
+        );
+    }
+
+    Ok(route_meta)
+}
+
 pub(crate) fn prepare_dataframes<P: AsRef<Path>>(
     path: P,
     departure: u32,
     duration: u32,
     weekday: &str,
-) -> Result<(DataFrame, DataFrame), Error> {
+    service_date: Option<&str>,
+) -> Result<PreparedFeed, Error> {
     validate_feed(&path)?;
     let gtfs_path = PathBuf::from(path.as_ref());
 
     let mut stops_df = read_csv(gtfs_path.join("stops.txt"))?;
-    let stop_times_df = read_csv(gtfs_path.join("stop_times.txt"))?;
+    let mut stop_times_df = read_csv(gtfs_path.join("stop_times.txt"))?;
     let mut trips_df = read_csv(gtfs_path.join("trips.txt"))?;
     let calendar_df = read_csv(gtfs_path.join("calendar.txt"))?;
+    let routes_df = read_csv(gtfs_path.join("routes.txt"))?;
+
+    let route_meta = read_route_meta(&routes_df)?;
+
+    let shapes_path = gtfs_path.join("shapes.txt");
+    let segment_map = if shapes_path.exists() {
+        let shapes_df = read_csv(shapes_path)?;
+        trip_geometry::split_segments_by_stops(
+            &shapes_df,
+            &stops_df,
+            &stop_times_df,
+            &trips_df,
+            DEFAULT_PROXIMITY_THRESHOLD_METERS,
+        )?
+    } else {
+        HashMap::new()
+    };
+
+    let transfers_path = gtfs_path.join("transfers.txt");
+    let transfers_df = transfers_path.exists().then(|| read_csv(transfers_path)).transpose()?;
+
+    let frequencies_path = gtfs_path.join("frequencies.txt");
+    if frequencies_path.exists() {
+        let frequencies_df = read_csv(frequencies_path)?;
+        let (expanded_stop_times, expanded_trips) =
+            expand_frequencies(&stop_times_df, &trips_df, &frequencies_df)?;
+        stop_times_df = expanded_stop_times;
+        trips_df = expanded_trips;
+    }
 
     let stops_df = stops_df
         .with_column(stops_df.column("stop_id")?.cast(&DataType::String)?)?
         .clone();
 
     // Filter calendar for active services on specific days (e.g., day_of_week == 1)
-    let service_ids = calendar_df
+    let mut service_ids = calendar_df
         .filter(
             &calendar_df
                 .column(weekday)?
@@ -112,6 +446,19 @@ pub(crate) fn prepare_dataframes<P: AsRef<Path>>(
         )?
         .select(["service_id"])?;
 
+    // A concrete service date overrides the weekday-derived set with
+    // `calendar_dates.txt`'s exceptions for that exact day, so added
+    // (exception_type 1) and removed (exception_type 2) services are
+    // reflected instead of only the recurring weekly pattern.
+    if let Some(service_date) = service_date {
+        let calendar_dates_path = gtfs_path.join("calendar_dates.txt");
+        if calendar_dates_path.exists() {
+            let calendar_dates_df = read_csv(calendar_dates_path)?;
+            service_ids =
+                apply_calendar_date_exceptions(&service_ids, &calendar_dates_df, service_date)?;
+        }
+    }
+
     // Join trips with active services to filter by service_id
     trips_df = trips_df.join(
         &service_ids,
@@ -132,18 +479,46 @@ pub(crate) fn prepare_dataframes<P: AsRef<Path>>(
         filter_by_time(&mut valid_stop_times, departure, departure + duration)?;
 
     println!("Filtering left {} rows", filtered_stop_times_df.height());
-    Ok((stops_df, filtered_stop_times_df))
+    Ok(PreparedFeed {
+        stops_df,
+        stop_times_df: filtered_stop_times_df,
+        route_meta,
+        segment_map,
+        transfers_df,
+    })
 }
 
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn new_graph(
     stops_df: &DataFrame,
     stop_times_df: &DataFrame,
+    interners: &mut Interners,
+    route_meta: &HashMap<String, RouteMeta>,
+    segment_map: &HashMap<(String, String), LineString<f64>>,
+    transfers_df: Option<&DataFrame>,
+    walk_speed: f64,
 ) -> Result<DiGraph<GraphNode, GraphEdge>, Error> {
     let mut transit_graph = DiGraph::<GraphNode, GraphEdge>::new();
     let mut node_id_map: HashMap<String, NodeIndex> = HashMap::new();
 
-    add_nodes_to_graph(stops_df, &mut transit_graph, &mut node_id_map)?;
-    add_edges_to_graph(stop_times_df, &mut transit_graph, &node_id_map)?;
+    add_nodes_to_graph(
+        stops_df,
+        &mut transit_graph,
+        &mut node_id_map,
+        &mut interners.stops,
+    )?;
+    add_edges_to_graph(
+        stop_times_df,
+        &mut transit_graph,
+        &node_id_map,
+        interners,
+        route_meta,
+        segment_map,
+    )?;
+
+    if let Some(transfers_df) = transfers_df {
+        add_transfers_to_graph(transfers_df, &mut transit_graph, &node_id_map, walk_speed)?;
+    }
 
     // sort trips by departure time
     for edge in transit_graph.edge_weights_mut() {
@@ -170,19 +545,36 @@ fn add_nodes_to_graph(
     stops_df: &DataFrame,
     transit_graph: &mut DiGraph<GraphNode, GraphEdge>,
     node_id_map: &mut HashMap<String, NodeIndex>,
+    stop_interner: &mut Interner,
 ) -> Result<(), Error> {
     let stop_ids = stops_df.column("stop_id")?.str()?.iter();
     let stop_lons = stops_df.column("stop_lon")?.f64()?.iter();
     let stop_lats = stops_df.column("stop_lat")?.f64()?.iter();
 
-    for (stop_id, (stop_lon, stop_lat)) in stop_ids.zip(stop_lons.zip(stop_lats)) {
+    // `wheelchair_boarding` is optional; GTFS treats a missing column or a
+    // `0`/`1` value as accessible, so only an explicit `2` marks a stop as
+    // not wheelchair accessible.
+    let wheelchair_boardings: Vec<bool> = match stops_df.column("wheelchair_boarding") {
+        Ok(col) => col
+            .cast(&DataType::Int64)?
+            .i64()?
+            .iter()
+            .map(|opt| opt != Some(2))
+            .collect(),
+        Err(_) => vec![true; stops_df.height()],
+    };
+
+    for ((stop_id, (stop_lon, stop_lat)), wheelchair_boarding) in
+        stop_ids.zip(stop_lons.zip(stop_lats)).zip(wheelchair_boardings)
+    {
         let stop_id = stop_id.ok_or_else(|| Error::MissingValue("stop_id".to_string()))?;
         let stop_lon = stop_lon.ok_or_else(|| Error::MissingValue("stop_lon".to_string()))?;
         let stop_lat = stop_lat.ok_or_else(|| Error::MissingValue("stop_lat".to_string()))?;
 
         let key = transit_graph.add_node(GraphNode::Transit(TransitNode {
-            stop_id: stop_id.to_string(),
+            stop_id: StopId(stop_interner.intern(stop_id)),
             geometry: Point::new(stop_lon, stop_lat),
+            wheelchair_boarding,
         }));
 
         node_id_map.insert(stop_id.to_string(), key);
@@ -191,28 +583,22 @@ fn add_nodes_to_graph(
 }
 
 fn select_columns(sorted_group: &DataFrame) -> Vec<&str> {
-    // Check if the wheelchair_accessible column exists and select columns accordingly
-    let columns: Vec<&str> = if sorted_group
-        .get_column_names_str()
-        .contains(&"wheelchair_accessible")
-    {
-        vec![
-            "arrival_time",
-            "departure_time",
-            "stop_id",
-            "stop_sequence",
-            "route_id",
-            "wheelchair_accessible",
-        ]
-    } else {
-        vec![
-            "arrival_time",
-            "departure_time",
-            "stop_id",
-            "stop_sequence",
-            "route_id",
-        ]
-    };
+    let names = sorted_group.get_column_names_str();
+    let mut columns = vec![
+        "arrival_time",
+        "departure_time",
+        "stop_id",
+        "stop_sequence",
+        "route_id",
+    ];
+
+    // Check whether these optional columns exist and select them accordingly
+    if names.contains(&"wheelchair_accessible") {
+        columns.push("wheelchair_accessible");
+    }
+    if names.contains(&"trip_headsign") {
+        columns.push("trip_headsign");
+    }
 
     columns
 }
@@ -221,15 +607,49 @@ fn add_edges_to_graph(
     stop_times_df: &DataFrame,
     transit_graph: &mut DiGraph<GraphNode, GraphEdge>,
     node_id_map: &HashMap<String, NodeIndex>,
+    interners: &mut Interners,
+    route_meta: &HashMap<String, RouteMeta>,
+    segment_map: &HashMap<(String, String), LineString<f64>>,
 ) -> Result<(), Error> {
     let grouped_df = stop_times_df.group_by(["trip_id"])?;
 
     grouped_df.apply(|group| {
         let sorted_group = group.sort(["stop_sequence"], SortMultipleOptions::default())?;
 
+        // `trip_id` is the group key, so every row shares the same value;
+        // interned once here and reused for every edge built from this group.
+        let trip_id = sorted_group
+            .column("trip_id")?
+            .cast(&DataType::String)?
+            .str()?
+            .get(0)
+            .ok_or_else(|| Error::MissingValue("trip_id".to_string()))?
+            .to_string();
+        let trip_id = TripId(interners.trips.intern(&trip_id));
+
         let columns = select_columns(&sorted_group);
         let selected_columns = sorted_group.select(columns)?;
 
+        // `trip_headsign` is per-trip rather than per-stop, so a single value
+        // (taken from the first row) covers every edge built from this group.
+        let trip_headsign: Option<String> = match selected_columns.column("trip_headsign") {
+            Ok(col) => {
+                let col = col.cast(&DataType::String)?;
+                col.str()?.get(0).map(str::to_string)
+            }
+            Err(_) => None,
+        };
+
+        // `wheelchair_accessible` is per-trip rather than per-stop (joined in
+        // from `trips.txt`), so a single value from the first row covers
+        // every edge built from this group. `1` is the GTFS value for
+        // "accessible"; missing or any other value is treated as not
+        // accessible.
+        let wheelchair_accessible = match selected_columns.column("wheelchair_accessible") {
+            Ok(col) => col.cast(&DataType::Int64)?.i64()?.get(0) == Some(1),
+            Err(_) => false,
+        };
+
         let stops = selected_columns
             .column("stop_id")?
             .cast(&DataType::String)?;
@@ -290,6 +710,12 @@ fn add_edges_to_graph(
                 depart_from_current_stop,
                 arrive_to_next_stop,
                 current_route_id,
+                trip_headsign.as_deref(),
+                wheelchair_accessible,
+                trip_id,
+                interners,
+                route_meta,
+                segment_map,
             )?;
         }
 
@@ -299,6 +725,7 @@ fn add_edges_to_graph(
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 fn add_edge_to_graph(
     transit_graph: &mut DiGraph<GraphNode, GraphEdge>,
     node_id_map: &HashMap<String, NodeIndex>,
@@ -307,12 +734,26 @@ fn add_edge_to_graph(
     depart_from_current_stop: u32,
     arrive_to_next_stop: u32,
     current_route_id: &str,
+    trip_headsign: Option<&str>,
+    wheelchair_accessible: bool,
+    trip_id: TripId,
+    interners: &mut Interners,
+    route_meta: &HashMap<String, RouteMeta>,
+    segment_map: &HashMap<(String, String), LineString<f64>>,
 ) -> Result<(), Error> {
+    let route_id = interners.intern_route(current_route_id, || {
+        route_meta.get(current_route_id).cloned().unwrap_or_default()
+    });
+    let trip_headsign =
+        trip_headsign.map(|headsign| HeadsignId(interners.headsigns.intern(headsign)));
+
     let route = Trip::new(
         depart_from_current_stop,
         arrive_to_next_stop,
-        String::from(current_route_id),
-        false,
+        route_id,
+        wheelchair_accessible,
+        trip_headsign,
+        trip_id,
     );
 
     let source_node = *node_id_map
@@ -328,11 +769,26 @@ fn add_edge_to_graph(
             transit_edge.edge_trips.push(route);
         }
     } else {
+        let geometry = segment_map
+            .get(&(current_stop.to_string(), next_stop.to_string()))
+            .cloned();
+        let boarding_wheelchair_accessible = match transit_graph.node_weight(source_node) {
+            Some(GraphNode::Transit(transit_node)) => transit_node.wheelchair_boarding,
+            _ => true,
+        };
+        // Interning here is idempotent: both stops were already interned
+        // while building nodes, so this just looks their ids back up.
+        let source_stop = StopId(interners.stops.intern(current_stop));
+        let target_stop = StopId(interners.stops.intern(next_stop));
         transit_graph.add_edge(
             source_node,
             target_node,
             GraphEdge::Transit(TransitEdge {
                 edge_trips: vec![route],
+                source_stop,
+                target_stop,
+                geometry,
+                boarding_wheelchair_accessible,
             }),
         );
     }
@@ -340,6 +796,79 @@ fn add_edge_to_graph(
     Ok(())
 }
 
+/// Materializes `transfers.txt` footpaths as [`GraphEdge::Transfer`] edges
+/// between the stops `from_stop_id`/`to_stop_id` map to. A row's weight is its
+/// `min_transfer_time` when present; otherwise it's the haversine distance
+/// between the two stops divided by `walk_speed`, so a configured interchange
+/// buffer is respected when the feed states one and a plausible walk time is
+/// still produced when it doesn't.
+///
+/// Rows naming a stop outside the filtered feed, or a stop transferring to
+/// itself, are skipped rather than erroring — `transfers.txt` routinely lists
+/// every same-station pair the agency considered, not just ones relevant to
+/// this graph.
+fn add_transfers_to_graph(
+    transfers_df: &DataFrame,
+    transit_graph: &mut DiGraph<GraphNode, GraphEdge>,
+    node_id_map: &HashMap<String, NodeIndex>,
+    walk_speed: f64,
+) -> Result<(), Error> {
+    let from_stops = transfers_df.column("from_stop_id")?.cast(&DataType::String)?;
+    let from_stops = from_stops.str()?.iter();
+    let to_stops = transfers_df.column("to_stop_id")?.cast(&DataType::String)?;
+    let to_stops = to_stops.str()?.iter();
+
+    let min_transfer_times: Vec<Option<u32>> = match transfers_df.column("min_transfer_time") {
+        Ok(col) => col.cast(&DataType::UInt32)?.u32()?.iter().collect(),
+        Err(_) => vec![None; transfers_df.height()],
+    };
+
+    for ((from_stop, to_stop), min_transfer_time) in
+        from_stops.zip(to_stops).zip(min_transfer_times)
+    {
+        let (Some(from_stop), Some(to_stop)) = (from_stop, to_stop) else {
+            continue;
+        };
+
+        if from_stop == to_stop {
+            continue;
+        }
+
+        let (Some(&source_node), Some(&target_node)) =
+            (node_id_map.get(from_stop), node_id_map.get(to_stop))
+        else {
+            continue;
+        };
+
+        let edge_weight = match min_transfer_time {
+            Some(seconds) => f64::from(seconds),
+            None => {
+                let geometries = transit_graph
+                    .node_weight(source_node)
+                    .map(GraphNode::geometry)
+                    .zip(transit_graph.node_weight(target_node).map(GraphNode::geometry));
+                let Some((source_geometry, target_geometry)) = geometries else {
+                    continue;
+                };
+                source_geometry.haversine_distance(target_geometry) / walk_speed
+            }
+        };
+
+        if transit_graph.find_edge(source_node, target_node).is_none() {
+            transit_graph.add_edge(
+                source_node,
+                target_node,
+                GraphEdge::Transfer(WalkEdge {
+                    edge_weight,
+                    geometry: None,
+                }),
+            );
+        }
+    }
+
+    Ok(())
+}
+
 pub(crate) fn merge_graphs<N, E>(walk_graph: &mut Graph<N, E>, transit_graph: &Graph<N, E>)
 where
     N: Clone,
@@ -378,7 +907,8 @@ mod tests {
 
         let mut graph = DiGraph::<GraphNode, GraphEdge>::new();
         let mut node_id_map = HashMap::new();
-        let result = add_nodes_to_graph(&df, &mut graph, &mut node_id_map);
+        let mut stop_interner = Interner::new();
+        let result = add_nodes_to_graph(&df, &mut graph, &mut node_id_map, &mut stop_interner);
 
         assert!(result.is_ok());
         assert_eq!(graph.node_count(), 3);
@@ -406,12 +936,23 @@ mod tests {
         }
         .unwrap();
 
-        add_nodes_to_graph(&stops_df, &mut graph, &mut node_id_map).unwrap();
+        let mut stop_interner = Interner::new();
+        let mut interners = Interners::new();
+        let route_meta = HashMap::new();
+        let segment_map = HashMap::new();
+        add_nodes_to_graph(&stops_df, &mut graph, &mut node_id_map, &mut stop_interner).unwrap();
 
         df.apply("arrival_time", hhmmss_to_sec).unwrap();
         df.apply("departure_time", hhmmss_to_sec).unwrap();
 
-        let result = add_edges_to_graph(&df, &mut graph, &node_id_map);
+        let result = add_edges_to_graph(
+            &df,
+            &mut graph,
+            &node_id_map,
+            &mut interners,
+            &route_meta,
+            &segment_map,
+        );
 
         assert!(result.is_ok());
         assert_eq!(graph.edge_count(), 2);
@@ -431,7 +972,10 @@ mod tests {
             assert_eq!(transit_edge1.edge_trips.len(), 1);
             assert_eq!(transit_edge1.edge_trips[0].departure_time, 29100); // 08:05:00 in seconds
             assert_eq!(transit_edge1.edge_trips[0].arrival_time, 29400); // 08:10:00 in seconds
-            assert_eq!(transit_edge1.edge_trips[0].route_id, "R1");
+            assert_eq!(
+                interners.routes.resolve(transit_edge1.edge_trips[0].route_id.0),
+                "R1"
+            );
         } else {
             panic!("Expected TransitEdge");
         }
@@ -440,36 +984,89 @@ mod tests {
             assert_eq!(transit_edge2.edge_trips.len(), 1);
             assert_eq!(transit_edge2.edge_trips[0].departure_time, 29700); // 08:15:00 in seconds
             assert_eq!(transit_edge2.edge_trips[0].arrival_time, 30300); // 08:25:00 in seconds
-            assert_eq!(transit_edge2.edge_trips[0].route_id, "R1");
+            assert_eq!(
+                interners.routes.resolve(transit_edge2.edge_trips[0].route_id.0),
+                "R1"
+            );
         } else {
             panic!("Expected TransitEdge");
         }
     }
 
+    #[test]
+    fn test_add_transfers_to_graph() {
+        let stops_df = df! {
+            "stop_id" => &["A", "B", "C"],
+            "stop_lon" => &[10.0, 10.0, 10.0],
+            "stop_lat" => &[50.0, 50.001, 60.0]
+        }
+        .unwrap();
+
+        let mut graph = DiGraph::<GraphNode, GraphEdge>::new();
+        let mut node_id_map = HashMap::new();
+        let mut stop_interner = Interner::new();
+        add_nodes_to_graph(&stops_df, &mut graph, &mut node_id_map, &mut stop_interner).unwrap();
+
+        let transfers_df = df! {
+            "from_stop_id" => &["A", "B", "C"],
+            "to_stop_id" => &["B", "A", "C"],
+            "min_transfer_time" => &[Some(120u32), None, None],
+        }
+        .unwrap();
+
+        add_transfers_to_graph(&transfers_df, &mut graph, &node_id_map, 1.39).unwrap();
+
+        // "C" -> "C" is a self-transfer and is skipped.
+        assert_eq!(graph.edge_count(), 2);
+
+        let a_to_b = graph.find_edge(node_id_map["A"], node_id_map["B"]).unwrap();
+        if let GraphEdge::Transfer(walk_edge) = graph.edge_weight(a_to_b).unwrap() {
+            assert!((walk_edge.edge_weight - 120.0).abs() < 1e-6);
+        } else {
+            panic!("Expected Transfer edge");
+        }
+
+        let b_to_a = graph.find_edge(node_id_map["B"], node_id_map["A"]).unwrap();
+        if let GraphEdge::Transfer(walk_edge) = graph.edge_weight(b_to_a).unwrap() {
+            // No min_transfer_time, so weight falls back to the haversine
+            // distance between the two stops divided by walk_speed.
+            assert!(walk_edge.edge_weight > 0.0);
+        } else {
+            panic!("Expected Transfer edge");
+        }
+    }
+
     #[test]
     fn test_merge_graphs() {
         let mut walk_graph = DiGraph::<GraphNode, GraphEdge>::new();
         let mut transit_graph = DiGraph::<GraphNode, GraphEdge>::new();
 
         let _ = walk_graph.add_node(GraphNode::Transit(TransitNode {
-            stop_id: "A".to_string(),
+            stop_id: StopId(0),
             geometry: Point::new(10.0, 50.0),
+            wheelchair_boarding: true,
         }));
 
         let node_b = transit_graph.add_node(GraphNode::Transit(TransitNode {
-            stop_id: "B".to_string(),
+            stop_id: StopId(1),
             geometry: Point::new(20.0, 60.0),
+            wheelchair_boarding: true,
         }));
         let node_c = transit_graph.add_node(GraphNode::Transit(TransitNode {
-            stop_id: "C".to_string(),
+            stop_id: StopId(2),
             geometry: Point::new(30.0, 70.0),
+            wheelchair_boarding: true,
         }));
 
         transit_graph.add_edge(
             node_b,
             node_c,
             GraphEdge::Transit(TransitEdge {
-                edge_trips: vec![Trip::new(0, 10, "R1".to_string(), false)],
+                edge_trips: vec![Trip::new(0, 10, RouteId(0), false, None, TripId(0))],
+                source_stop: StopId(1),
+                target_stop: StopId(2),
+                geometry: None,
+                boarding_wheelchair_accessible: true,
             }),
         );
 
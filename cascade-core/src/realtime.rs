@@ -0,0 +1,185 @@
+//! Live delay overlay decoded from a GTFS-Realtime `TripUpdates` feed.
+//!
+//! The base [`TransitGraph`] and its schedule are entirely static; a
+//! [`RealtimeOverlay`] carries the live adjustments on top of it instead of
+//! mutating the graph, so one graph can keep serving concurrent queries
+//! while a caller swaps in a freshly decoded overlay every refresh cycle.
+//! Path searches take the overlay by reference (see
+//! [`crate::graph::TransitEdge::next_trip`]), so the common, overlay-less
+//! path stays exactly as cheap as before this module existed.
+
+use ahash::{HashMap, HashMapExt};
+use hashbrown::HashSet;
+
+use gtfs_rt::trip_descriptor::ScheduleRelationship;
+use gtfs_rt::FeedMessage;
+use prost::Message;
+
+use crate::graph::TransitGraph;
+use crate::interner::{StopId, TripId};
+use crate::Error;
+
+/// A trip's live arrival/departure deltas at one stop, in seconds, as
+/// reported by a GTFS-Realtime `StopTimeUpdate`. Positive values mean the
+/// trip is running late; negative values mean it's running early.
+#[derive(Debug, Clone, Copy, Default)]
+struct StopTimeDelay {
+    arrival_delay: i32,
+    departure_delay: i32,
+}
+
+/// Per-`(trip, stop)` delay adjustments and trip cancellations decoded from a
+/// GTFS-Realtime `FeedMessage`, attachable to path queries against a
+/// [`TransitGraph`] by reference without mutating it. See the module docs.
+#[derive(Debug, Clone, Default)]
+pub struct RealtimeOverlay {
+    delays: HashMap<(TripId, StopId), StopTimeDelay>,
+    cancelled_trips: HashSet<TripId>,
+}
+
+impl RealtimeOverlay {
+    #[must_use]
+    pub(crate) fn new() -> Self {
+        Self {
+            delays: HashMap::new(),
+            cancelled_trips: HashSet::new(),
+        }
+    }
+
+    pub(crate) fn set_cancelled(&mut self, trip_id: TripId) {
+        self.cancelled_trips.insert(trip_id);
+    }
+
+    pub(crate) fn set_delay(
+        &mut self,
+        trip_id: TripId,
+        stop_id: StopId,
+        departure_delay: i32,
+        arrival_delay: i32,
+    ) {
+        self.delays.insert(
+            (trip_id, stop_id),
+            StopTimeDelay {
+                arrival_delay,
+                departure_delay,
+            },
+        );
+    }
+
+    /// Whether the GTFS-Realtime feed reported `trip_id` as cancelled.
+    #[must_use]
+    pub(crate) fn is_canceled(&self, trip_id: TripId) -> bool {
+        self.cancelled_trips.contains(&trip_id)
+    }
+
+    /// Shifts `scheduled` by `trip_id`'s reported departure delay at
+    /// `stop_id`, or returns it unchanged if the feed didn't cover that
+    /// `(trip, stop)` pair.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub(crate) fn adjust_departure(&self, trip_id: TripId, stop_id: StopId, scheduled: u32) -> u32 {
+        self.delays
+            .get(&(trip_id, stop_id))
+            .map_or(scheduled, |delay| {
+                (i64::from(scheduled) + i64::from(delay.departure_delay)).max(0) as u32
+            })
+    }
+
+    /// Shifts `scheduled` by `trip_id`'s reported arrival delay at
+    /// `stop_id`, or returns it unchanged if the feed didn't cover that
+    /// `(trip, stop)` pair.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub(crate) fn adjust_arrival(&self, trip_id: TripId, stop_id: StopId, scheduled: u32) -> u32 {
+        self.delays
+            .get(&(trip_id, stop_id))
+            .map_or(scheduled, |delay| {
+                (i64::from(scheduled) + i64::from(delay.arrival_delay)).max(0) as u32
+            })
+    }
+
+    /// Decodes a GTFS-Realtime `FeedMessage` and resolves its `trip_id`s and
+    /// `stop_id`s against `graph`'s already-built interners. Trip updates
+    /// and stop-time updates referencing ids `graph` never interned (e.g. a
+    /// trip outside the static feed's loaded window) are skipped rather than
+    /// rejecting the whole feed, since a live feed routinely covers more of
+    /// the schedule than any single static window does.
+    ///
+    /// # Errors
+    /// Returns [`Error::InvalidData`] if `bytes` isn't a valid
+    /// `FeedMessage` protobuf.
+    pub fn from_protobuf(bytes: &[u8], graph: &TransitGraph) -> Result<Self, Error> {
+        let feed = FeedMessage::decode(bytes)
+            .map_err(|e| Error::InvalidData(format!("invalid GTFS-Realtime feed: {e}")))?;
+
+        let mut overlay = Self::new();
+
+        for entity in feed.entity {
+            let Some(trip_update) = entity.trip_update else {
+                continue;
+            };
+            let Some(trip_id_str) = trip_update.trip.trip_id.as_deref() else {
+                continue;
+            };
+            let Some(trip_id) = graph.trip_id(trip_id_str) else {
+                continue;
+            };
+
+            if trip_update.trip.schedule_relationship
+                == Some(ScheduleRelationship::Canceled as i32)
+            {
+                overlay.set_cancelled(trip_id);
+                continue;
+            }
+
+            for stop_time_update in trip_update.stop_time_update {
+                let Some(stop_id_str) = stop_time_update.stop_id.as_deref() else {
+                    continue;
+                };
+                let Some(stop_id) = graph.stop_id(stop_id_str) else {
+                    continue;
+                };
+
+                let departure_delay = stop_time_update
+                    .departure
+                    .as_ref()
+                    .and_then(|event| event.delay)
+                    .unwrap_or(0);
+                let arrival_delay = stop_time_update
+                    .arrival
+                    .as_ref()
+                    .and_then(|event| event.delay)
+                    .unwrap_or(0);
+
+                overlay.set_delay(trip_id, stop_id, departure_delay, arrival_delay);
+            }
+        }
+
+        Ok(overlay)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_adjust_departure_and_arrival() {
+        let mut overlay = RealtimeOverlay::new();
+        overlay.set_delay(TripId(0), StopId(0), 120, 90);
+
+        assert_eq!(overlay.adjust_departure(TripId(0), StopId(0), 1000), 1120);
+        assert_eq!(overlay.adjust_arrival(TripId(0), StopId(0), 1000), 1090);
+        // No entry for this stop: unchanged.
+        assert_eq!(overlay.adjust_departure(TripId(0), StopId(1), 1000), 1000);
+    }
+
+    #[test]
+    fn test_is_canceled() {
+        let mut overlay = RealtimeOverlay::new();
+        overlay.set_cancelled(TripId(5));
+
+        assert!(overlay.is_canceled(TripId(5)));
+        assert!(!overlay.is_canceled(TripId(6)));
+    }
+}
@@ -7,6 +7,10 @@ use rstar::RTree;
 
 use crate::Error;
 
+/// Default proximity threshold used by [`crate::loaders::prepare_dataframes`]
+/// when splitting `shapes.txt` geometry around stops.
+pub(crate) const DEFAULT_PROXIMITY_THRESHOLD_METERS: f64 = 10.0;
+
 type RTreeStop = GeomWithData<Point, String>;
 
 fn create_stops_rtree(stops_df: &DataFrame) -> Result<RTree<RTreeStop>, Error> {
@@ -40,9 +44,196 @@ fn find_closest_stop(point: &Point, rtree: &RTree<RTreeStop>) -> Result<(String,
     }
 }
 
+/// Splits each `shape_id` in `shapes_df` into per-stop-pair [`LineString`]s.
+///
+/// When `shapes.txt` and `stop_times.txt` both carry `shape_dist_traveled`,
+/// each shape is cut at the exact cumulative distance of the stops it serves
+/// ([`split_segments_by_distance`]) — precise even for loop routes and
+/// closely-spaced stops, where nearest-stop snapping misassigns points.
+/// Otherwise this falls back to snapping shape points to the nearest stop in
+/// `stops_df`, governed by `proximity_threshold_meters`: how close (in
+/// meters) a shape point must be to a stop before it is treated as passing
+/// through that stop. GTFS feeds vary widely in how tightly their shapes hug
+/// stop locations, so this is left for the caller to tune rather than
+/// hardcoded.
 pub(crate) fn split_segments_by_stops(
     shapes_df: &DataFrame,
     stops_df: &DataFrame,
+    stop_times_df: &DataFrame,
+    trips_df: &DataFrame,
+    proximity_threshold_meters: f64,
+) -> Result<HashMap<(String, String), LineString<f64>>, Error> {
+    let has_dist_traveled = shapes_df.column("shape_dist_traveled").is_ok()
+        && stop_times_df.column("shape_dist_traveled").is_ok();
+
+    if has_dist_traveled {
+        return split_segments_by_distance(shapes_df, stop_times_df, trips_df);
+    }
+
+    split_segments_by_nearest_stop(shapes_df, stops_df, proximity_threshold_meters)
+}
+
+/// Cuts each shape at the exact interpolated position of every stop it
+/// serves, using `shape_dist_traveled` from `shapes.txt`/`stop_times.txt`
+/// rather than geometric proximity.
+///
+/// Only one representative trip per `shape_id` is read from `stop_times_df`:
+/// all trips sharing a shape serve the same stops at the same cumulative
+/// distances along it, so reading more than one would just redo the same
+/// cut.
+fn split_segments_by_distance(
+    shapes_df: &DataFrame,
+    stop_times_df: &DataFrame,
+    trips_df: &DataFrame,
+) -> Result<HashMap<(String, String), LineString<f64>>, Error> {
+    let shape_points = collect_shape_points(shapes_df)?;
+
+    let representative_trips = trips_df
+        .select(["trip_id", "shape_id"])?
+        .unique(Some(&["shape_id".to_string()]), UniqueKeepStrategy::First, None)?;
+
+    let trip_stops = stop_times_df.join(
+        &representative_trips,
+        ["trip_id"],
+        ["trip_id"],
+        JoinArgs::new(JoinType::Inner),
+    )?;
+
+    let mut segment_map = HashMap::new();
+    let grouped_trip_stops = trip_stops.group_by(["shape_id"])?;
+
+    grouped_trip_stops.apply(|group| {
+        let group = group.sort(["stop_sequence"], SortMultipleOptions::default())?;
+
+        let shape_id = group
+            .column("shape_id")?
+            .cast(&DataType::String)?
+            .str()?
+            .get(0)
+            .ok_or_else(|| Error::MissingValue("shape_id".to_string()))?
+            .to_string();
+
+        let Some(points) = shape_points.get(&shape_id) else {
+            return Ok(group);
+        };
+
+        let stop_ids = group.column("stop_id")?.cast(&DataType::String)?;
+        let stop_ids = stop_ids.str()?.iter();
+        let distances = group.column("shape_dist_traveled")?.f64()?.iter();
+
+        let mut prev: Option<(String, f64)> = None;
+        for (stop_id, distance) in stop_ids.zip(distances) {
+            let stop_id = stop_id
+                .ok_or_else(|| Error::MissingValue("stop_id".to_string()))?
+                .to_string();
+            let distance =
+                distance.ok_or_else(|| Error::MissingValue("shape_dist_traveled".to_string()))?;
+
+            if let Some((prev_stop_id, prev_distance)) = prev.take() {
+                let key = (prev_stop_id.clone(), stop_id.clone());
+                let linestring = cut_linestring_between(points, prev_distance, distance);
+                segment_map.insert(key, linestring);
+            }
+
+            prev = Some((stop_id, distance));
+        }
+
+        Ok(group)
+    })?;
+
+    Ok(segment_map)
+}
+
+/// Groups `shapes_df` by `shape_id` into `(shape_dist_traveled, Point)`
+/// pairs sorted by `shape_pt_sequence`, ready for interpolation.
+fn collect_shape_points(shapes_df: &DataFrame) -> Result<HashMap<String, Vec<(f64, Point)>>, Error> {
+    let mut shape_points = HashMap::new();
+    let grouped_shapes = shapes_df.group_by(["shape_id"])?;
+
+    grouped_shapes.apply(|group| {
+        let group = group.sort(["shape_pt_sequence"], SortMultipleOptions::default())?;
+
+        let shape_id = group
+            .column("shape_id")?
+            .cast(&DataType::String)?
+            .str()?
+            .get(0)
+            .ok_or_else(|| Error::MissingValue("shape_id".to_string()))?
+            .to_string();
+
+        let lats = group.column("shape_pt_lat")?.f64()?.iter();
+        let lons = group.column("shape_pt_lon")?.f64()?.iter();
+        let dists = group.column("shape_dist_traveled")?.f64()?.iter();
+
+        let mut points = Vec::with_capacity(group.height());
+        for ((lat_opt, lon_opt), dist_opt) in lats.zip(lons).zip(dists) {
+            let lat = lat_opt.ok_or_else(|| Error::MissingValue("shape_pt_lat".to_string()))?;
+            let lon = lon_opt.ok_or_else(|| Error::MissingValue("shape_pt_lon".to_string()))?;
+            let dist = dist_opt
+                .ok_or_else(|| Error::MissingValue("shape_dist_traveled".to_string()))?;
+            points.push((dist, Point::new(lon, lat)));
+        }
+
+        shape_points.insert(shape_id, points);
+        Ok(group)
+    })?;
+
+    Ok(shape_points)
+}
+
+/// Linearly interpolates the point on `points` (sorted ascending by
+/// cumulative distance) at `target_dist`, clamping to the first/last point
+/// if `target_dist` falls outside the shape's range.
+fn interpolate_at_distance(points: &[(f64, Point)], target_dist: f64) -> Point {
+    let Some(&(first_dist, first_point)) = points.first() else {
+        return Point::new(0.0, 0.0);
+    };
+    if target_dist <= first_dist {
+        return first_point;
+    }
+
+    for window in points.windows(2) {
+        let (start_dist, start_point) = window[0];
+        let (end_dist, end_point) = window[1];
+
+        if target_dist <= end_dist {
+            if (end_dist - start_dist).abs() < f64::EPSILON {
+                return end_point;
+            }
+            let t = (target_dist - start_dist) / (end_dist - start_dist);
+            return Point::new(
+                start_point.x() + t * (end_point.x() - start_point.x()),
+                start_point.y() + t * (end_point.y() - start_point.y()),
+            );
+        }
+    }
+
+    points.last().map_or(first_point, |&(_, point)| point)
+}
+
+/// Builds the [`LineString`] between `start_dist` and `end_dist` along
+/// `points`: the interpolated endpoints plus every shape vertex strictly
+/// between them.
+fn cut_linestring_between(points: &[(f64, Point)], start_dist: f64, end_dist: f64) -> LineString<f64> {
+    let mut coords = vec![interpolate_at_distance(points, start_dist)];
+
+    coords.extend(
+        points
+            .iter()
+            .filter(|&&(dist, _)| dist > start_dist && dist < end_dist)
+            .map(|&(_, point)| point),
+    );
+
+    coords.push(interpolate_at_distance(points, end_dist));
+    LineString::from(coords)
+}
+
+/// Splits each `shape_id` in `shapes_df` into per-stop-pair [`LineString`]s by
+/// snapping shape points to the nearest stop in `stops_df`.
+fn split_segments_by_nearest_stop(
+    shapes_df: &DataFrame,
+    stops_df: &DataFrame,
+    proximity_threshold_meters: f64,
 ) -> Result<HashMap<(String, String), LineString<f64>>, Error> {
     let rtree = create_stops_rtree(stops_df)?;
 
@@ -69,8 +260,7 @@ pub(crate) fn split_segments_by_stops(
 
             // Use find_closest_stop to identify nearby stops
             if let Ok((stop_id, distance)) = find_closest_stop(&point, &rtree) {
-                if distance < 10.0 {
-                    println!("{point:?}, {stop_id}, {distance}");
+                if distance < proximity_threshold_meters {
                     // If within proximity of a stop, finalize the current segment
                     if let Some(prev_id) = &prev_stop_id {
                         let key = (prev_id.clone(), stop_id.clone());
@@ -100,8 +290,5 @@ pub(crate) fn split_segments_by_stops(
         Ok(group)
     })?;
 
-    // print map length
-    println!("Map length is {}", segment_map.len());
-
     Ok(segment_map)
 }
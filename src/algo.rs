@@ -36,12 +36,17 @@ print(
 ```
 */
 
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc;
+
 use ahash::{HashMap, HashMapExt};
 
 use cascade_core::prelude::*;
 use geo::Point;
+use polars::prelude::*;
 use pyo3::prelude::*;
 use pyo3::types::PyString;
+use pyo3_polars::PyDataFrame;
 use rayon::prelude::*;
 
 use crate::graph::PyTransitGraph;
@@ -88,13 +93,104 @@ pub fn single_source_shortest_path_weight(
     x: f64,
     y: f64,
 ) -> PyResult<HashMap<usize, f64>> {
+    let overlay = graph.realtime_overlay.as_ref();
     let graph = &graph.graph;
     let source = snap_point(x, y, graph)?;
 
-    let hmap = cascade_core::algo::single_source_shortest_path_weight(graph, &source, dep_time)
-        .into_iter()
-        .map(|(k, v)| (k.index(), v))
-        .collect();
+    let hmap =
+        cascade_core::algo::single_source_shortest_path_weight(graph, &source, dep_time, overlay)
+            .into_iter()
+            .map(|(k, v)| (k.index(), v))
+            .collect();
+
+    Ok(hmap)
+}
+
+/// Finds the shortest path from the source point to all other nodes, like
+/// [`single_source_shortest_path_weight`], but accesses the graph through
+/// the `k` nearest stops to `(x, y)` on foot instead of only the single
+/// nearest one — useful when a rider could plausibly walk to more than one
+/// nearby stop before boarding.
+///
+/// Parameters
+/// ----------
+/// graph: PyTransitGraph
+///     The graph to search for the shortest path.
+/// dep_time: int
+///     The starting time.
+/// x: float
+///     Latitude of the source point.
+/// y: float
+///     Longitude of the source point.
+/// k: int
+///     The number of nearest stops to snap to.
+///
+/// Returns
+/// -------
+/// Dict[int, float]
+///     Dict with shortest distances from the source to all nodes.
+#[pyfunction]
+pub fn single_source_shortest_path_weight_knn(
+    graph: &PyTransitGraph,
+    dep_time: u32,
+    x: f64,
+    y: f64,
+    k: usize,
+) -> PyResult<HashMap<usize, f64>> {
+    let overlay = graph.realtime_overlay.as_ref();
+    let graph = &graph.graph;
+    let sources = SnappedPoint::init_knn(Point::new(x, y), graph, k);
+
+    let hmap = cascade_core::algo::single_source_shortest_path_weight_multi(
+        graph, &sources, dep_time, overlay,
+    )
+    .into_iter()
+    .map(|(node, v)| (node.index(), v))
+    .collect();
+
+    Ok(hmap)
+}
+
+/// Finds the shortest path from the source point to all other nodes, like
+/// [`single_source_shortest_path_weight`], but accesses the graph through
+/// every stop within `radius_meters` of `(x, y)` on foot instead of only
+/// the single nearest one.
+///
+/// Parameters
+/// ----------
+/// graph: PyTransitGraph
+///     The graph to search for the shortest path.
+/// dep_time: int
+///     The starting time.
+/// x: float
+///     Latitude of the source point.
+/// y: float
+///     Longitude of the source point.
+/// radius_meters: float
+///     The walking radius, in meters, within which stops are used as access points.
+///
+/// Returns
+/// -------
+/// Dict[int, float]
+///     Dict with shortest distances from the source to all nodes.
+#[pyfunction]
+pub fn single_source_shortest_path_weight_within(
+    graph: &PyTransitGraph,
+    dep_time: u32,
+    x: f64,
+    y: f64,
+    radius_meters: f64,
+) -> PyResult<HashMap<usize, f64>> {
+    let overlay = graph.realtime_overlay.as_ref();
+    let graph = &graph.graph;
+    let sources = SnappedPoint::init_within(Point::new(x, y), graph, radius_meters);
+
+    let hmap = cascade_core::algo::single_source_shortest_path_weight_multi(
+        graph, &sources, dep_time, overlay,
+    )
+    .into_iter()
+    .map(|(node, v)| (node.index(), v))
+    .collect();
 
     Ok(hmap)
 }
@@ -117,13 +213,18 @@ pub fn single_source_shortest_path_weight(
 ///     The x coordinate of the target point in 4326.
 /// target_y : float
 ///     The y coordinate of the target point in 4326.
+/// astar : bool, optional
+///     Whether to guide the search with a geographic lower-bound heuristic instead of
+///     exploring uninformed, which is substantially faster on city-scale graphs. Defaults
+///     to True; both modes are exact and always agree on the result.
 ///
 /// Returns
 /// -------
 /// float
 ///     Weight of the shortest path in seconds.
 #[pyfunction]
-#[pyo3(name = "shortest_path_weight")]
+#[pyo3(name = "shortest_path_weight", signature = (graph, dep_time, source_x, source_y, target_x, target_y, astar=true))]
+#[allow(clippy::too_many_arguments)]
 pub fn shortest_path_weight(
     graph: &PyTransitGraph,
     dep_time: u32,
@@ -131,26 +232,51 @@ pub fn shortest_path_weight(
     source_y: f64,
     target_x: f64,
     target_y: f64,
+    astar: bool,
 ) -> PyResult<f64> {
+    let overlay = graph.realtime_overlay.as_ref();
     let graph = &graph.graph;
 
     let source = snap_point(source_x, source_y, graph)?;
     let target = snap_point(target_x, target_y, graph)?;
 
-    let result = cascade_core::algo::shortest_path_weight(graph, &source, &target, dep_time)
-        .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(format!("{e:?}")))?;
+    let result = cascade_core::algo::shortest_path_weight(
+        graph, &source, &target, dep_time, overlay, astar,
+    )
+    .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(format!("{e:?}")))?;
 
     Ok(result)
 }
 
 /// Calculate an origin-destination (OD) matrix for a set of points, providing the shortest path weights between all pairs of points
+///
+/// When `cache` is given (see [`build_distance_cache`]), each origin is answered from the
+/// cache's precomputed anchor trees instead of a fresh global search — an approximation,
+/// and only valid when `cache` was built for the same `dep_time` as this call.
+///
+/// `num_threads` sizes a dedicated `rayon` thread pool for the per-origin searches; pass
+/// `0` to let `rayon` pick the default (number of logical CPUs).
+///
+/// When `progress` is given, it's called roughly every `progress_interval_secs` seconds
+/// with `(completed, total, elapsed_seconds)`. Returning `False` from the callback (or
+/// raising) requests cooperative cancellation: origins still in flight finish, but any
+/// origin not yet started is skipped and comes back with an empty row.
 #[pyfunction]
+#[pyo3(signature = (graph, nodes, dep_time, cache=None, num_threads=0, progress=None, progress_interval_secs=2.0))]
+#[allow(clippy::too_many_arguments)]
 pub fn calculate_od_matrix(
+    py: Python<'_>,
     graph: &PyTransitGraph,
     nodes: Vec<PyPoint>,
     dep_time: u32,
+    cache: Option<&PyLandmarkCache>,
+    num_threads: usize,
+    progress: Option<PyObject>,
+    progress_interval_secs: f64,
 ) -> PyResult<HashMap<String, HashMap<String, f64>>> {
+    let overlay = graph.realtime_overlay.as_ref();
     let graph = &graph.graph;
+    let cache = cache.map(|cache| &cache.cache);
 
     let snapped_points: Vec<(String, SnappedPoint)> = nodes
         .into_iter()
@@ -165,25 +291,269 @@ pub fn calculate_od_matrix(
         .map(|(id, sn)| (sn.index().index(), id))
         .collect();
 
-    // Collect the OD matrix with PyPoint IDs as keys
-    let od_matrix: HashMap<String, HashMap<String, f64>> = snapped_points
-        .par_iter()
-        .map(|(id, node)| {
-            let mut shortest_paths = HashMap::with_capacity(snapped_points.len());
-            for (k, v) in
-                cascade_core::algo::single_source_shortest_path_weight(graph, node, dep_time)
-            {
-                if let Some(&dest_id) = id_map.get(&k.index()) {
-                    shortest_paths.insert(dest_id.clone(), v); // Directly insert into pre-allocated map
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(num_threads)
+        .build()
+        .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))?;
+
+    let total = snapped_points.len();
+    let completed = AtomicUsize::new(0);
+    let cancelled = AtomicBool::new(false);
+    let (tick_tx, tick_rx) = mpsc::sync_channel::<()>(1);
+
+    let od_matrix: HashMap<String, HashMap<String, f64>> = py.allow_threads(|| {
+        std::thread::scope(|scope| {
+            // `install` only applies to the thread that calls it, so the
+            // searches enter `pool` from inside the worker thread itself.
+            let worker = scope.spawn(|| {
+                pool.install(|| {
+                    snapped_points
+                        .par_iter()
+                        .map(|(id, node)| {
+                            if cancelled.load(Ordering::Relaxed) {
+                                return (id.clone(), HashMap::new());
+                            }
+
+                            let mut shortest_paths = HashMap::with_capacity(snapped_points.len());
+                            let scores = match cache {
+                                Some(cache) => cache.scores_from(graph, node, overlay),
+                                None => cascade_core::algo::single_source_shortest_path_weight(
+                                    graph, node, dep_time, overlay,
+                                ),
+                            };
+                            for (k, v) in scores {
+                                if let Some(&dest_id) = id_map.get(&k.index()) {
+                                    shortest_paths.insert(dest_id.clone(), v);
+                                }
+                            }
+
+                            completed.fetch_add(1, Ordering::Relaxed);
+                            let _ = tick_tx.try_send(());
+
+                            (id.clone(), shortest_paths)
+                        })
+                        .collect::<HashMap<String, HashMap<String, f64>>>()
+                })
+            });
+
+            let start = std::time::Instant::now();
+            let interval = std::time::Duration::from_secs_f64(progress_interval_secs.max(0.01));
+            let mut last_report = start;
+
+            while !worker.is_finished() {
+                let _ = tick_rx.recv_timeout(interval);
+
+                // Ticks wake the loop as origins complete; the callback itself
+                // only runs once `interval` has passed since the last call.
+                if last_report.elapsed() < interval {
+                    continue;
+                }
+                last_report = std::time::Instant::now();
+
+                if let Some(callback) = &progress {
+                    let keep_going = Python::with_gil(|py| -> PyResult<bool> {
+                        callback
+                            .call1(
+                                py,
+                                (
+                                    completed.load(Ordering::Relaxed),
+                                    total,
+                                    start.elapsed().as_secs_f64(),
+                                ),
+                            )?
+                            .extract::<bool>(py)
+                    });
+
+                    if !matches!(keep_going, Ok(true)) {
+                        cancelled.store(true, Ordering::Relaxed);
+                    }
                 }
             }
-            (id.clone(), shortest_paths)
+
+            worker.join().expect("OD matrix worker thread panicked")
         })
-        .collect();
+    });
 
     Ok(od_matrix)
 }
 
+/// Builds a precomputed [`PyLandmarkCache`] from a fixed set of anchor points at a given
+/// departure time, for fast repeated [`calculate_od_matrix`]/[`travel_time_matrix`] calls
+/// against the same graph and departure window.
+///
+/// Parameters
+/// ----------
+/// graph: PyTransitGraph
+///     The graph to precompute anchor trees against.
+/// anchors: List[PyPoint]
+///     The landmark points whose shortest-path trees get cached. More anchors improve
+///     accuracy (fewer queries fall back to a long local search) at the cost of more
+///     memory and build time.
+/// dep_time: int
+///     The departure time this cache is valid for.
+///
+/// Returns
+/// -------
+/// PyLandmarkCache
+///     A reusable cache handle, passed as the `cache` argument to `calculate_od_matrix`
+///     and `travel_time_matrix`.
+#[pyfunction]
+pub fn build_distance_cache(
+    graph: &PyTransitGraph,
+    anchors: Vec<PyPoint>,
+    dep_time: u32,
+) -> PyResult<PyLandmarkCache> {
+    let overlay = graph.realtime_overlay.as_ref();
+    let graph = &graph.graph;
+
+    let snapped_anchors: Vec<SnappedPoint> = anchors
+        .into_iter()
+        .map(|py_point| snap_point(py_point.x, py_point.y, graph))
+        .collect::<Result<_, _>>()?;
+
+    let cache = graph.build_cache(&snapped_anchors, dep_time, overlay);
+
+    Ok(PyLandmarkCache { cache })
+}
+
+/// Reusable precomputed distance cache built by [`build_distance_cache`].
+#[pyclass]
+pub struct PyLandmarkCache {
+    cache: cascade_core::algo::LandmarkCache,
+}
+
+#[pymethods]
+impl PyLandmarkCache {
+    /// The departure time this cache was built for.
+    #[getter]
+    fn dep_time(&self) -> u32 {
+        self.cache.dep_time()
+    }
+}
+
+/// Finds the visiting order of `nodes` minimizing total travel time, starting from `nodes[0]`.
+///
+/// Parameters
+/// ----------
+/// graph: PyTransitGraph
+///     The graph to search for shortest paths between stops.
+/// nodes: List[PyPoint]
+///     The stops to visit. The tour always starts at `nodes[0]`.
+/// dep_time: int
+///     The starting time.
+/// roundtrip: bool
+///     Whether the tour must return to `nodes[0]` after visiting every other stop.
+///
+/// Returns
+/// -------
+/// Tuple[List[str], float]
+///     The visited point IDs in order, and the tour's total weight in seconds.
+///
+/// Notes
+/// -----
+/// Delegates to [`cascade_core::algo::optimal_tour`]: up to 12 waypoints are ordered
+/// exactly via Held-Karp bitmask DP, and larger tours fall back to a nearest-neighbor
+/// seed improved with 2-opt.
+#[pyfunction]
+pub fn optimize_tour(
+    graph: &PyTransitGraph,
+    nodes: Vec<PyPoint>,
+    dep_time: u32,
+    roundtrip: bool,
+) -> PyResult<(Vec<String>, f64)> {
+    let overlay = graph.realtime_overlay.as_ref();
+    let graph = &graph.graph;
+
+    let snapped_points: Vec<(String, SnappedPoint)> = nodes
+        .into_iter()
+        .map(|py_point| {
+            snap_point(py_point.x, py_point.y, graph).map(|snapped| (py_point.id, snapped))
+        })
+        .collect::<Result<_, _>>()?;
+
+    let ids: Vec<String> = snapped_points.iter().map(|(id, _)| id.clone()).collect();
+
+    if ids.is_empty() {
+        return Ok((Vec::new(), 0.0));
+    }
+
+    let points: Vec<SnappedPoint> = snapped_points.into_iter().map(|(_, point)| point).collect();
+
+    let (order, itinerary) =
+        cascade_core::algo::optimal_tour(graph, &points, dep_time, roundtrip, overlay)
+            .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(format!("{e:?}")))?;
+
+    let ordered_ids = order.into_iter().map(|i| ids[i].clone()).collect();
+
+    Ok((ordered_ids, itinerary.duration()))
+}
+
+/// Computes a travel-time matrix from many origins to every node reachable from them,
+/// running one shortest-path query per origin in parallel on a dedicated thread pool.
+///
+/// When `cache` is given (see [`build_distance_cache`]), each origin is instead answered
+/// from the cache's precomputed anchor trees, trading a small amount of accuracy for
+/// skipping a fresh global search per origin. Only pass a cache built for the same
+/// `dep_time` as this call.
+///
+/// Returns a `polars` `DataFrame` with `origin`, `destination`, and `seconds` columns,
+/// one row per reachable origin/destination pair. Unreachable destinations are simply
+/// omitted rather than given an explicit `null`/`inf` row.
+#[pyfunction]
+#[pyo3(signature = (graph, sources, dep_time, num_threads=0, cache=None))]
+pub fn travel_time_matrix(
+    graph: &PyTransitGraph,
+    sources: Vec<PyPoint>,
+    dep_time: u32,
+    num_threads: usize,
+    cache: Option<&PyLandmarkCache>,
+) -> PyResult<PyDataFrame> {
+    let overlay = graph.realtime_overlay.as_ref();
+    let graph = &graph.graph;
+    let cache = cache.map(|cache| &cache.cache);
+
+    let snapped_points: Vec<(String, SnappedPoint)> = sources
+        .into_iter()
+        .map(|py_point| {
+            snap_point(py_point.x, py_point.y, graph).map(|snapped| (py_point.id, snapped))
+        })
+        .collect::<Result<_, _>>()?;
+
+    let origin_ids: Vec<String> = snapped_points.iter().map(|(id, _)| id.clone()).collect();
+    let points: Vec<SnappedPoint> = snapped_points.into_iter().map(|(_, point)| point).collect();
+
+    let weights = cascade_core::algo::travel_time_matrix(
+        graph,
+        &points,
+        dep_time,
+        num_threads,
+        overlay,
+        cache,
+    )
+    .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(format!("{e:?}")))?;
+
+    let mut origins = Vec::new();
+    let mut destinations = Vec::new();
+    let mut seconds = Vec::new();
+
+    for (origin_id, reached) in origin_ids.iter().zip(weights.iter()) {
+        for (destination, weight) in reached {
+            origins.push(origin_id.clone());
+            destinations.push(destination.index() as u64);
+            seconds.push(*weight);
+        }
+    }
+
+    let df = df! {
+        "origin" => origins,
+        "destination" => destinations,
+        "seconds" => seconds,
+    }
+    .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(format!("{e:?}")))?;
+
+    Ok(PyDataFrame(df))
+}
+
 pub(crate) fn snap_point(x: f64, y: f64, graph: &TransitGraph) -> PyResult<SnappedPoint> {
     SnappedPoint::init(Point::new(x, y), graph).map_err(|e| {
         pyo3::exceptions::PyRuntimeError::new_err(format!("Failed to snap point: {e:?}"))
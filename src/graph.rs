@@ -38,22 +38,47 @@ use pyo3::types::PyString;
 ///     Time period from departure for which the graph will be loaded.
 /// weekday : str
 ///     Day of the week in lowercase (e.g., 'monday').
+/// service_date : str, optional
+///     Concrete service date in `YYYYMMDD` format. When set, service
+///     exceptions from `calendar_dates.txt` for that exact day override the
+///     `weekday`-derived set of active services, so routing reflects the
+///     actual schedule on that day rather than just its weekday. Ignored if
+///     the feed has no `calendar_dates.txt`.
+/// cache_dir : str, optional
+///     Directory for a persistent on-disk graph cache. When set, a graph
+///     built for the same inputs is served from disk instead of being
+///     rebuilt from the GTFS feed and OSM extract.
+/// walk_speed : float, optional
+///     Pedestrian walking speed in meters/second, used to weight transfer
+///     edges between stops and the street network. Defaults to 1.39 m/s.
+/// max_transfer_distance : float, optional
+///     Maximum distance in meters a stop may be connected to the street
+///     network over. Defaults to 1000.0 m.
 ///
 /// Returns
 /// -------
 /// PyTransitGraph
 ///     Combined multimodal graph representing transit network.
 #[pyfunction]
-#[pyo3(name = "create_graph")]
+#[pyo3(
+    name = "create_graph",
+    signature = (gtfs_path, pbf_path, departure, duration, weekday, service_date=None, cache_dir=None, walk_speed=1.39, max_transfer_distance=1000.0)
+)]
+#[allow(clippy::too_many_arguments)]
 pub fn create_graph(
     gtfs_path: &str,
     pbf_path: &str,
     departure: u32,
     duration: u32,
     weekday: &str,
+    service_date: Option<&str>,
+    cache_dir: Option<&str>,
+    walk_speed: f64,
+    max_transfer_distance: f64,
 ) -> PyResult<PyTransitGraph> {
     let gtfs_path = std::path::PathBuf::from(gtfs_path);
     let pbf_path = std::path::PathBuf::from(pbf_path);
+    let cache_dir = cache_dir.map(std::path::PathBuf::from);
 
     let feed_args = FeedArgs {
         gtfs_path,
@@ -61,21 +86,37 @@ pub fn create_graph(
         departure,
         duration,
         weekday,
+        service_date,
+        cache_dir: cache_dir.clone(),
+        walk_speed,
+        max_transfer_distance,
     };
     let instant = std::time::Instant::now();
-    let graph = TransitGraph::from(feed_args).map_err(|e| {
+    let graph = match cache_dir {
+        Some(cache_dir) => TransitGraph::from_cached(feed_args, &cache_dir),
+        None => TransitGraph::from(feed_args),
+    }
+    .map_err(|e| {
         pyo3::exceptions::PyRuntimeError::new_err(format!("Graph creation failed: {e:?}"))
     })?;
 
     println!("Graph creation time: {:?}", instant.elapsed());
 
-    Ok(PyTransitGraph { graph })
+    Ok(PyTransitGraph {
+        graph,
+        realtime_overlay: None,
+    })
 }
 
 /// Multimodal graph of transit system, implemented with `PetGraph`
 #[pyclass]
 pub struct PyTransitGraph {
     pub graph: TransitGraph,
+    /// Live delay overlay pushed via [`PyTransitGraph::push_realtime_update`],
+    /// if a GTFS-Realtime feed snapshot has been attached. Held on the
+    /// Python wrapper rather than the core graph, so the base graph keeps
+    /// serving queries while the overlay is periodically swapped out.
+    pub realtime_overlay: Option<cascade_core::realtime::RealtimeOverlay>,
 }
 
 #[pymethods]
@@ -102,7 +143,7 @@ impl PyTransitGraph {
                 GraphNode::Transit(transit_node) => {
                     let graph_node = PyGraphNode {
                         node_type: "transit".to_string(),
-                        id: transit_node.stop_id.clone(),
+                        id: graph.stop_id_str(transit_node.stop_id).to_string(),
                         geometry: transit_node.geometry,
                     };
                     id_mapping.insert(node.index(), graph_node);
@@ -132,16 +173,22 @@ impl PyTransitGraph {
     ///     Time period from departure for which the graph will be loaded.
     /// weekday : str
     ///     Day of the week in lowercase (e.g., 'monday').
+    /// service_date : str, optional
+    ///     Concrete service date in `YYYYMMDD` format, overriding the
+    ///     `weekday`-derived set of active services with
+    ///     `calendar_dates.txt`'s exceptions for that exact day.
     ///
     /// Returns
     /// -------
     /// None
+    #[pyo3(signature = (gtfs_path, departure, duration, weekday, service_date=None))]
     pub fn extend_with_transit(
         &mut self,
         gtfs_path: &str,
         departure: u32,
         duration: u32,
         weekday: &str,
+        service_date: Option<&str>,
     ) -> PyResult<()> {
         let gtfs_path = std::path::PathBuf::from(gtfs_path);
         // dummy pbf path, not used in this case
@@ -154,6 +201,11 @@ impl PyTransitGraph {
             departure,
             duration,
             weekday,
+            service_date,
+            cache_dir: None,
+            // dummy values, not used by `extend_with_transit`
+            walk_speed: 1.39,
+            max_transfer_distance: 1000.0,
         };
 
         self.graph.extend_with_transit(&feed_args).map_err(|e| {
@@ -162,6 +214,74 @@ impl PyTransitGraph {
 
         Ok(())
     }
+
+    /// Serializes this graph to `path` in a compact binary format, for
+    /// later restoring with [`PyTransitGraph::load`] instead of rebuilding
+    /// from the GTFS feed and OSM extract.
+    ///
+    /// Parameters
+    /// ----------
+    /// path : str
+    ///     File path to write the serialized graph to.
+    ///
+    /// Returns
+    /// -------
+    /// None
+    pub fn save(&self, path: &str) -> PyResult<()> {
+        self.graph.save(std::path::Path::new(path)).map_err(|e| {
+            pyo3::exceptions::PyRuntimeError::new_err(format!("Failed to save graph: {e:?}"))
+        })
+    }
+
+    /// Loads a `PyTransitGraph` previously written by [`PyTransitGraph::save`].
+    ///
+    /// Parameters
+    /// ----------
+    /// path : str
+    ///     File path to read the serialized graph from.
+    ///
+    /// Returns
+    /// -------
+    /// PyTransitGraph
+    #[staticmethod]
+    pub fn load(path: &str) -> PyResult<PyTransitGraph> {
+        let graph = TransitGraph::load(std::path::Path::new(path)).map_err(|e| {
+            pyo3::exceptions::PyRuntimeError::new_err(format!("Failed to load graph: {e:?}"))
+        })?;
+
+        Ok(PyTransitGraph {
+            graph,
+            realtime_overlay: None,
+        })
+    }
+
+    /// Decodes a GTFS-Realtime `FeedMessage` and attaches it to this graph
+    /// as a live delay overlay, replacing any previously attached snapshot.
+    /// Pathfinding queries made against this graph after this call reflect
+    /// the feed's reported delays and cancellations; the underlying static
+    /// graph itself is left untouched.
+    ///
+    /// Parameters
+    /// ----------
+    /// feed_bytes : bytes
+    ///     Serialized GTFS-Realtime `FeedMessage` protobuf.
+    ///
+    /// Returns
+    /// -------
+    /// None
+    pub fn push_realtime_update(&mut self, feed_bytes: &[u8]) -> PyResult<()> {
+        let overlay =
+            cascade_core::realtime::RealtimeOverlay::from_protobuf(feed_bytes, &self.graph)
+                .map_err(|e| {
+                    pyo3::exceptions::PyRuntimeError::new_err(format!(
+                        "Failed to decode GTFS-Realtime feed: {e:?}"
+                    ))
+                })?;
+
+        self.realtime_overlay = Some(overlay);
+
+        Ok(())
+    }
 }
 
 
@@ -50,5 +50,111 @@ pub fn detailed_itinerary(
     let itinerary =
         cascade_core::algo::detailed_itinerary(graph, &source, &target, dep_time, wheelchair);
 
-    Ok(itinerary.to_geojson().to_string())
+    Ok(itinerary.to_geojson(graph).to_string())
+}
+
+/// Computes a detailed itinerary like [`detailed_itinerary`], but `max_speed` (in
+/// meters/second) is supplied by the caller instead of being derived automatically
+/// from the graph's fastest edge, letting the A* heuristic be tightened when the
+/// network's true top speed is known in advance.
+///
+/// Returns
+/// -------
+/// str
+///     Itinerary in GeoJSON format.
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+pub fn detailed_itinerary_astar(
+    graph: &PyTransitGraph,
+    dep_time: u32,
+    source_x: f64,
+    source_y: f64,
+    target_x: f64,
+    target_y: f64,
+    wheelchair: bool,
+    max_speed: f64,
+) -> PyResult<String> {
+    let graph = &graph.graph;
+
+    let source = snap_point(source_x, source_y, graph)?;
+    let target = snap_point(target_x, target_y, graph)?;
+
+    let itinerary = cascade_core::algo::detailed_itinerary_astar(
+        graph, &source, &target, dep_time, wheelchair, max_speed, None,
+    );
+
+    Ok(itinerary.to_geojson(graph).to_string())
+}
+
+/// Finds up to `k` loopless itineraries from the source to the target, in increasing
+/// total travel time, so riders can compare transfers-vs-time tradeoffs instead of only
+/// ever seeing the single fastest option.
+///
+/// Returns
+/// -------
+/// List[str]
+///     Itineraries in `GeoJSON` format, fastest first. Fewer than `k` are returned if
+///     that many loopless alternatives don't exist.
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+pub fn k_shortest_itineraries(
+    graph: &PyTransitGraph,
+    dep_time: u32,
+    source_x: f64,
+    source_y: f64,
+    target_x: f64,
+    target_y: f64,
+    k: usize,
+    wheelchair: bool,
+) -> PyResult<Vec<String>> {
+    let graph = &graph.graph;
+
+    let source = snap_point(source_x, source_y, graph)?;
+    let target = snap_point(target_x, target_y, graph)?;
+
+    let itineraries = cascade_core::algo::k_shortest_itineraries(
+        graph, &source, &target, dep_time, k, wheelchair, None,
+    );
+
+    Ok(itineraries
+        .iter()
+        .map(|itinerary| itinerary.to_geojson(graph).to_string())
+        .collect())
+}
+
+/// Finds every co-optimal itinerary from the source to the target — every loopless path
+/// that ties for the fastest possible arrival, rather than just one of them.
+///
+/// `limit` bounds how many are returned, since the number of co-optimal paths can grow
+/// exponentially when many parallel routes share the same optimal travel time.
+///
+/// Returns
+/// -------
+/// List[str]
+///     Co-optimal itineraries in `GeoJSON` format. Empty if the target is unreachable.
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+pub fn all_optimal_itineraries(
+    graph: &PyTransitGraph,
+    dep_time: u32,
+    source_x: f64,
+    source_y: f64,
+    target_x: f64,
+    target_y: f64,
+    wheelchair: bool,
+    limit: usize,
+) -> PyResult<Vec<String>> {
+    let graph = &graph.graph;
+
+    let source = snap_point(source_x, source_y, graph)?;
+    let target = snap_point(target_x, target_y, graph)?;
+
+    let itineraries = cascade_core::algo::all_optimal_itineraries(
+        graph, &source, &target, dep_time, wheelchair, None, limit,
+    );
+
+    Ok(itineraries
+        .iter()
+        .map(|itinerary| itinerary.to_geojson(graph).to_string())
+        .collect())
 }
@@ -46,12 +46,18 @@ graph = create_graph(gtfs_path, pbf_path, departure, duration, weekday)
 use pyo3::prelude::*;
 
 use crate::algo::{
-    calculate_od_matrix, shortest_path, shortest_path_weight, single_source_shortest_path, PyPoint,
+    build_distance_cache, calculate_od_matrix, optimize_tour, shortest_path, shortest_path_weight,
+    single_source_shortest_path, single_source_shortest_path_weight_knn,
+    single_source_shortest_path_weight_within, travel_time_matrix, PyLandmarkCache, PyPoint,
 };
 use crate::graph::{create_graph, PyTransitGraph};
+use crate::itinerary::{
+    all_optimal_itineraries, detailed_itinerary, detailed_itinerary_astar, k_shortest_itineraries,
+};
 
 pub mod algo;
 pub mod graph;
+pub mod itinerary;
 
 #[pymodule]
 fn _cascade_core(m: &Bound<'_, PyModule>) -> PyResult<()> {
@@ -60,7 +66,17 @@ fn _cascade_core(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(shortest_path_weight, m)?)?;
     m.add_function(wrap_pyfunction!(create_graph, m)?)?;
     m.add_function(wrap_pyfunction!(calculate_od_matrix, m)?)?;
+    m.add_function(wrap_pyfunction!(optimize_tour, m)?)?;
+    m.add_function(wrap_pyfunction!(travel_time_matrix, m)?)?;
+    m.add_function(wrap_pyfunction!(build_distance_cache, m)?)?;
+    m.add_function(wrap_pyfunction!(single_source_shortest_path_weight_knn, m)?)?;
+    m.add_function(wrap_pyfunction!(single_source_shortest_path_weight_within, m)?)?;
+    m.add_function(wrap_pyfunction!(detailed_itinerary, m)?)?;
+    m.add_function(wrap_pyfunction!(detailed_itinerary_astar, m)?)?;
+    m.add_function(wrap_pyfunction!(k_shortest_itineraries, m)?)?;
+    m.add_function(wrap_pyfunction!(all_optimal_itineraries, m)?)?;
     m.add_class::<PyTransitGraph>()?;
     m.add_class::<PyPoint>()?;
+    m.add_class::<PyLandmarkCache>()?;
     Ok(())
 }
@@ -15,6 +15,10 @@ fn main_zheleznogorsk_test() {
         departure: 0,
         duration: 90000,
         weekday: "monday",
+        service_date: None,
+        cache_dir: None,
+        walk_speed: 1.39,
+        max_transfer_distance: 1000.0,
     };
     let departure_time = 43200;
 
@@ -25,8 +29,9 @@ fn main_zheleznogorsk_test() {
     let target = SnappedPoint::init(Point::new(93.554203, 56.237849), &transit_graph)
         .expect("Failed to concstruct snapped point");
 
-    let weights = single_source_shortest_path_weight(&transit_graph, &source, departure_time);
-    let path = detailed_itinerary(&transit_graph, &source, &target, departure_time, false);
+    let weights =
+        single_source_shortest_path_weight(&transit_graph, &source, departure_time, None);
+    let path = detailed_itinerary(&transit_graph, &source, &target, departure_time, false, None);
 
     let weight = weights.get(target.index()).expect("Node should be reached");
 
@@ -39,3 +44,69 @@ fn main_zheleznogorsk_test() {
         }
     ));
 }
+
+/// Compares [`LandmarkCache::distance`]/[`LandmarkCache::scores_from`] against an exact
+/// [`single_source_shortest_path_weight`] on the Zheleznogorsk fixture, with the cache's
+/// sole anchor placed away from both the query source and target so the comparison
+/// actually exercises the cache's local-search-to-anchor-plus-tree approximation instead
+/// of degenerating into the anchor's own exact tree.
+#[allow(clippy::cast_possible_truncation)]
+#[test]
+fn landmark_cache_matches_exact_dijkstra_on_zheleznogorsk() {
+    let gtfs_path: PathBuf = "tests/test_data/Zhelez".into();
+    let pbf_path: PathBuf = "tests/test_data/roads_zhelez.pbf".into();
+
+    let feed_args = FeedArgs {
+        gtfs_path,
+        pbf_path,
+        departure: 0,
+        duration: 90000,
+        weekday: "monday",
+        service_date: None,
+        cache_dir: None,
+        walk_speed: 1.39,
+        max_transfer_distance: 1000.0,
+    };
+    let departure_time = 43200;
+
+    let transit_graph = TransitGraph::from(feed_args).expect("Failed to construct Transit Graph");
+
+    let source = SnappedPoint::init(Point::new(93.528906, 56.245849), &transit_graph)
+        .expect("Failed to construct snapped point");
+    let target = SnappedPoint::init(Point::new(93.554203, 56.237849), &transit_graph)
+        .expect("Failed to construct snapped point");
+    let anchor = SnappedPoint::init(Point::new(93.541500, 56.258000), &transit_graph)
+        .expect("Failed to construct snapped point");
+
+    let cache = transit_graph.build_cache(&[anchor], departure_time, None);
+
+    let exact = single_source_shortest_path_weight(&transit_graph, &source, departure_time, None);
+    let exact_weight = *exact
+        .get(target.index())
+        .expect("target should be reachable from source");
+
+    let cached_weight = cache
+        .distance(&transit_graph, &source, &target, None)
+        .expect("target should be reachable via the anchor's cached tree");
+
+    // The cache's bound is source -> (local search) -> anchor -> (cached exact tree) ->
+    // target, a valid but generally suboptimal path, so by the triangle inequality it
+    // can only ever overstate the exact weight, never understate it.
+    let tolerance = 1e-6;
+    assert!(
+        cached_weight + tolerance >= exact_weight,
+        "cached weight {cached_weight} must be at least the exact weight {exact_weight}"
+    );
+    // With the anchor placed close to this source/target pair, the detour through it
+    // shouldn't more than double the true travel time.
+    assert!(
+        cached_weight <= exact_weight * 2.0 + tolerance,
+        "cached weight {cached_weight} strayed too far from exact weight {exact_weight}"
+    );
+
+    let cached_scores = cache.scores_from(&transit_graph, &source, None);
+    let cached_score = cached_scores
+        .get(target.index())
+        .expect("scores_from should include the target via the anchor's cached tree");
+    assert!((*cached_score - cached_weight).abs() < tolerance);
+}